@@ -0,0 +1,31 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use group::{Curve, Group};
+use rust_protodanksharding_example::{commit_key::CommitKeyLagrange, polynomial::Polynomial};
+
+fn bench_commit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("commit");
+    group.sample_size(10);
+
+    let size = 4096;
+    let points: Vec<_> = (0..size)
+        .map(|_| blstrs::G1Projective::random(&mut rand::thread_rng()).to_affine())
+        .collect();
+    let polynomial = Polynomial::new((0..size).map(|_| blstrs::Scalar::random(&mut rand::thread_rng())).collect());
+
+    let commit_key = CommitKeyLagrange::new(points);
+    group.bench_function("lincomb", |b| b.iter(|| commit_key.commit(&polynomial)));
+
+    let mut commit_key = commit_key;
+    for window_size in [4, 8, 10] {
+        commit_key.precompute(window_size);
+        group.bench_with_input(BenchmarkId::new("precomputed", window_size), &polynomial, |b, polynomial| {
+            b.iter(|| commit_key.commit(polynomial))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_commit);
+criterion_main!(benches);
@@ -0,0 +1,82 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use group::{prime::PrimeCurveAffine, Curve, Group};
+use rust_protodanksharding_example::domain::Domain;
+
+// The recursive FFT that `Domain` used before the iterative, in-place version.
+// It is kept here as a baseline to compare against.
+fn recursive_fft_g1(nth_root_of_unity: blstrs::Scalar, points: &[blstrs::G1Projective]) -> Vec<blstrs::G1Projective> {
+    let n = points.len();
+    if n == 1 {
+        return points.to_vec();
+    }
+
+    let even: Vec<_> = points.iter().step_by(2).copied().collect();
+    let odd: Vec<_> = points.iter().skip(1).step_by(2).copied().collect();
+
+    // Compute a root with half the order
+    let gen_squared = nth_root_of_unity.square();
+
+    let fft_even = recursive_fft_g1(gen_squared, &even);
+    let fft_odd = recursive_fft_g1(gen_squared, &odd);
+
+    let mut input_point = blstrs::Scalar::one();
+    let mut evaluations = vec![blstrs::G1Projective::identity(); n];
+
+    for k in 0..n / 2 {
+        let tmp = fft_odd[k] * input_point;
+        evaluations[k] = fft_even[k] + tmp;
+        evaluations[k + n / 2] = fft_even[k] - tmp;
+
+        input_point *= nth_root_of_unity;
+    }
+
+    evaluations
+}
+
+fn recursive_ifft_g1(domain: &Domain, points: Vec<blstrs::G1Affine>) -> Vec<blstrs::G1Affine> {
+    let points_proj: Vec<_> = points.into_iter().map(blstrs::G1Projective::from).collect();
+
+    let mut ifft_g1 = recursive_fft_g1(domain.generator_inv, &points_proj);
+    for element in ifft_g1.iter_mut() {
+        *element *= domain.domain_size_inv
+    }
+
+    let mut affine = vec![blstrs::G1Affine::identity(); ifft_g1.len()];
+    blstrs::G1Projective::batch_normalize(&ifft_g1, &mut affine);
+    affine
+}
+
+fn bench_ifft_g1(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ifft_g1");
+    group.sample_size(10);
+
+    for size in [256, 4096] {
+        let domain = Domain::new(size);
+        let points: Vec<_> = (0..size)
+            .map(|_| blstrs::G1Projective::random(&mut rand::thread_rng()).to_affine())
+            .collect();
+
+        assert_eq!(domain.ifft_g1(points.clone()), recursive_ifft_g1(&domain, points.clone()));
+
+        group.bench_with_input(BenchmarkId::new("iterative", size), &points, |b, points| {
+            b.iter(|| domain.ifft_g1(points.clone()))
+        });
+        group.bench_with_input(BenchmarkId::new("recursive", size), &points, |b, points| {
+            b.iter(|| recursive_ifft_g1(&domain, points.clone()))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_fft_scalars(c: &mut Criterion) {
+    let size = 4096;
+    let domain = Domain::new(size);
+    let coeffs: Vec<_> = (0..size).map(|_| blstrs::Scalar::random(&mut rand::thread_rng())).collect();
+
+    c.bench_function("fft_scalars/4096", |b| b.iter(|| domain.fft_scalars(coeffs.clone())));
+}
+
+criterion_group!(benches, bench_ifft_g1, bench_fft_scalars);
+criterion_main!(benches);
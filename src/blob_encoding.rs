@@ -0,0 +1,193 @@
+use std::fmt;
+
+use crate::{eip4844::FIELD_ELEMENTS_PER_BLOB, polynomial::Polynomial, SCALAR_SERIALIZED_SIZE};
+
+// The number of payload bytes stored in each field element.
+// The most significant byte of each field element is always zero, so the element is less than the BLS modulus
+pub const USABLE_BYTES_PER_FIELD_ELEMENT: usize = SCALAR_SERIALIZED_SIZE - 1;
+// The number of payload bytes that fit in one blob, including the length prefix
+pub const USABLE_BYTES_PER_BLOB: usize = USABLE_BYTES_PER_FIELD_ELEMENT * FIELD_ELEMENTS_PER_BLOB;
+// The length of the payload is stored as a big-endian u64 before the payload
+pub const LENGTH_PREFIX_SIZE: usize = 8;
+
+/// Packs arbitrary bytes into as many blobs as needed
+///
+/// The payload is prefixed with its length and split into chunks of `USABLE_BYTES_PER_FIELD_ELEMENT` bytes,
+/// each of which is stored big-endian in one field element. The last blob is padded with zeros.
+/// An empty payload is encoded as a single blob, so that its length can be recovered.
+pub fn encode_payload(payload: &[u8]) -> Vec<Polynomial> {
+    let mut stream = Vec::with_capacity(LENGTH_PREFIX_SIZE + payload.len());
+    stream.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+    stream.extend_from_slice(payload);
+
+    let num_blobs = stream.len().div_ceil(USABLE_BYTES_PER_BLOB);
+    stream.resize(num_blobs * USABLE_BYTES_PER_BLOB, 0);
+
+    stream
+        .chunks_exact(USABLE_BYTES_PER_BLOB)
+        .map(|blob_bytes| {
+            let evaluations = blob_bytes
+                .chunks_exact(USABLE_BYTES_PER_FIELD_ELEMENT)
+                .map(|chunk| {
+                    let mut bytes = [0u8; SCALAR_SERIALIZED_SIZE];
+                    bytes[1..].copy_from_slice(chunk);
+                    // The top byte is zero, so this is always a canonical scalar
+                    blstrs::Scalar::from_bytes_be(&bytes).unwrap()
+                })
+                .collect();
+            Polynomial::new(evaluations)
+        })
+        .collect()
+}
+
+/// Unpacks the payload from blobs that were created with `encode_payload`
+///
+/// Returns an error if the blobs could not have been created by `encode_payload`,
+/// so that every payload has exactly one encoding
+pub fn decode_payload(polynomials: &[Polynomial]) -> Result<Vec<u8>, BlobEncodingError> {
+    if polynomials.is_empty() {
+        return Err(BlobEncodingError::NoBlobs);
+    }
+
+    let mut stream = Vec::with_capacity(polynomials.len() * USABLE_BYTES_PER_BLOB);
+    for (blob_index, polynomial) in polynomials.iter().enumerate() {
+        if polynomial.evaluations.len() != FIELD_ELEMENTS_PER_BLOB {
+            return Err(BlobEncodingError::InvalidBlobSize {
+                expected: FIELD_ELEMENTS_PER_BLOB,
+                actual: polynomial.evaluations.len(),
+            });
+        }
+
+        for (index, evaluation) in polynomial.evaluations.iter().enumerate() {
+            let bytes = evaluation.to_bytes_be();
+            if bytes[0] != 0 {
+                return Err(BlobEncodingError::InvalidFieldElement { blob_index, index });
+            }
+            stream.extend_from_slice(&bytes[1..]);
+        }
+    }
+
+    let (length, rest) = stream.split_at(LENGTH_PREFIX_SIZE);
+    let length = u64::from_be_bytes(length.try_into().unwrap());
+    let num_blobs = length.saturating_add(LENGTH_PREFIX_SIZE as u64).div_ceil(USABLE_BYTES_PER_BLOB as u64);
+    if num_blobs.max(1) != polynomials.len() as u64 {
+        return Err(BlobEncodingError::InvalidLength(length));
+    }
+
+    let (payload, padding) = rest.split_at(length as usize);
+    if padding.iter().any(|byte| *byte != 0) {
+        return Err(BlobEncodingError::NonZeroPadding);
+    }
+
+    Ok(payload.to_vec())
+}
+
+/// Errors that can occur when decoding a payload from blobs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobEncodingError {
+    /// At least one blob is needed to store the length of the payload
+    NoBlobs,
+    /// A blob does not have `FIELD_ELEMENTS_PER_BLOB` evaluations
+    InvalidBlobSize { expected: usize, actual: usize },
+    /// The most significant byte of a field element is not zero
+    InvalidFieldElement { blob_index: usize, index: usize },
+    /// The length prefix does not match the number of blobs
+    InvalidLength(u64),
+    /// The bytes after the end of the payload are not all zero
+    NonZeroPadding,
+}
+
+impl fmt::Display for BlobEncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlobEncodingError::NoBlobs => write!(f, "at least one blob is needed to decode a payload"),
+            BlobEncodingError::InvalidBlobSize { expected, actual } => {
+                write!(f, "blobs must have {expected} field elements, got {actual}")
+            }
+            BlobEncodingError::InvalidFieldElement { blob_index, index } => {
+                write!(f, "field element {index} of blob {blob_index} does not start with a zero byte")
+            }
+            BlobEncodingError::InvalidLength(length) => {
+                write!(f, "a payload of {length} bytes does not match the number of blobs")
+            }
+            BlobEncodingError::NonZeroPadding => write!(f, "the padding after the payload is not zero"),
+        }
+    }
+}
+
+impl std::error::Error for BlobEncodingError {}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use rand::RngCore;
+
+    use super::*;
+    use crate::{domain::Domain, eip4844::blob_to_polynomial, params::PublicParameters};
+
+    fn random_payload(length: usize) -> Vec<u8> {
+        let mut payload = vec![0u8; length];
+        rand::thread_rng().fill_bytes(&mut payload);
+        payload
+    }
+
+    #[test]
+    fn payload_roundtrip() {
+        // The first blob holds the length prefix, so a payload of `max_one_blob` bytes fills it exactly
+        let max_one_blob = USABLE_BYTES_PER_BLOB - LENGTH_PREFIX_SIZE;
+        let cases = [(0, 1), (1, 1), (100, 1), (max_one_blob, 1), (max_one_blob + 1, 2), (3 * USABLE_BYTES_PER_BLOB, 4)];
+
+        for (length, num_blobs) in cases {
+            let payload = random_payload(length);
+            let polynomials = encode_payload(&payload);
+            assert_eq!(polynomials.len(), num_blobs);
+            assert_eq!(decode_payload(&polynomials), Ok(payload));
+        }
+    }
+
+    #[test]
+    fn encoded_payload_can_be_committed_to() {
+        let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+        let public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+
+        let polynomial = encode_payload(b"hello world").remove(0);
+
+        // The evaluations serialize to a valid blob, which commits to the same polynomial
+        let blob: Vec<u8> = polynomial.evaluations.iter().flat_map(|evaluation| evaluation.to_bytes_be()).collect();
+        let blob_polynomial = blob_to_polynomial(blob.as_slice().try_into().unwrap()).unwrap();
+        assert_eq!(blob_polynomial, polynomial);
+        assert_eq!(
+            public_parameters.commit_key.commit(&polynomial),
+            public_parameters.commit_key.commit(&blob_polynomial)
+        );
+    }
+
+    #[test]
+    fn invalid_encodings_are_rejected() {
+        let polynomials = encode_payload(b"hello world");
+        assert_eq!(decode_payload(&[]), Err(BlobEncodingError::NoBlobs));
+
+        let mut invalid_element = polynomials.clone();
+        invalid_element[0].evaluations[5] = -blstrs::Scalar::one();
+        assert_eq!(
+            decode_payload(&invalid_element),
+            Err(BlobEncodingError::InvalidFieldElement { blob_index: 0, index: 5 })
+        );
+
+        let mut non_zero_padding = polynomials.clone();
+        non_zero_padding[0].evaluations[FIELD_ELEMENTS_PER_BLOB - 1] = blstrs::Scalar::one();
+        assert_eq!(decode_payload(&non_zero_padding), Err(BlobEncodingError::NonZeroPadding));
+
+        // An extra blob of padding is not allowed
+        let mut extra_blob = polynomials.clone();
+        extra_blob.push(Polynomial::new(vec![blstrs::Scalar::zero(); FIELD_ELEMENTS_PER_BLOB]));
+        assert_eq!(decode_payload(&extra_blob), Err(BlobEncodingError::InvalidLength(11)));
+
+        // The length prefix is stored in the bytes after the zero byte of the first field element
+        let mut too_long = polynomials;
+        let mut first_element = [0u8; SCALAR_SERIALIZED_SIZE];
+        first_element[1..1 + LENGTH_PREFIX_SIZE].fill(0xff);
+        too_long[0].evaluations[0] = blstrs::Scalar::from_bytes_be(&first_element).unwrap();
+        assert_eq!(decode_payload(&too_long), Err(BlobEncodingError::InvalidLength(u64::MAX)));
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{domain::Domain, polynomial::Polynomial};
+use crate::{
+    domain::Domain, error::KzgError, fixed_base_msm::FixedBaseMsm, polynomial::Polynomial,
+    utils::bit_reversal_permutation,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 // The key that is used to commit to polynomials in monomial form
 //
@@ -10,16 +16,30 @@ pub struct CommitKey { inner: Vec<blstrs::G1Affine>, }
 
 impl CommitKey {
     pub fn new(points: Vec<blstrs::G1Affine>) -> CommitKey {
-        assert!(
-            !points.is_empty(),
-            "cannot initialize `CommitKey` with no points"
-        );
-        CommitKey { inner: points }
+        CommitKey::try_new(points).unwrap_or_else(|err| panic!("cannot initialize `CommitKey`: {err}"))
+    }
+
+    pub fn try_new(points: Vec<blstrs::G1Affine>) -> Result<CommitKey, KzgError> {
+        if points.is_empty() {
+            return Err(KzgError::TooFewPoints { minimum: 1, actual: 0 });
+        }
+        Ok(CommitKey { inner: points })
+    }
+
+    pub(crate) fn points(&self) -> &[blstrs::G1Affine] {
+        &self.inner
     }
 
     // Note: There is no commit method for CommitKey in monomial basis as this is not used
+    //
+    // The lagrange points are stored in the same order as the roots of `domain`
     pub fn into_lagrange(self, domain: &Domain) -> CommitKeyLagrange {
-        CommitKeyLagrange { inner: domain.ifft_g1(self.inner) }
+        let inner = domain.ifft_g1(self.inner);
+        if domain.is_bit_reversed() {
+            CommitKeyLagrange { inner: bit_reversal_permutation(&inner), precomputed: None }
+        } else {
+            CommitKeyLagrange { inner, precomputed: None }
+        }
     }
 }
 
@@ -30,17 +50,52 @@ impl CommitKey {
 /// - `i` ranges from 0 to `degree`
 /// -  L_i is the i'th lagrange polynomial
 /// - `G` is some generator of the group
-pub struct CommitKeyLagrange { inner: Vec<blstrs::G1Affine>, }
+pub struct CommitKeyLagrange {
+    inner: Vec<blstrs::G1Affine>,
+    // Tables of multiples of `inner`, which are used to speed up `commit` if present
+    precomputed: Option<FixedBaseMsm>,
+}
 
 impl CommitKeyLagrange {
     pub fn new(points: Vec<blstrs::G1Affine>) -> CommitKeyLagrange {
-        assert!(points.len() > 1);
-        CommitKeyLagrange { inner: points }
+        CommitKeyLagrange::try_new(points)
+            .unwrap_or_else(|err| panic!("cannot initialize `CommitKeyLagrange`: {err}"))
+    }
+
+    pub fn try_new(points: Vec<blstrs::G1Affine>) -> Result<CommitKeyLagrange, KzgError> {
+        if points.len() < 2 {
+            return Err(KzgError::TooFewPoints { minimum: 2, actual: points.len() });
+        }
+        Ok(CommitKeyLagrange { inner: points, precomputed: None })
+    }
+
+    /// Precomputes fixed-base tables for the points, so that later calls to `commit` are faster
+    ///
+    /// Larger window sizes give faster commitments, but the tables take up
+    /// `num_points * 2^(window_size - 1) * 96` bytes. See `FixedBaseMsm` for details.
+    ///
+    /// Panics, if `window_size` is 0 or larger than `FixedBaseMsm::MAX_WINDOW_SIZE`
+    pub fn precompute(&mut self, window_size: usize) {
+        self.precomputed = Some(FixedBaseMsm::new(&self.inner, window_size));
+    }
+
+    /// Same as `precompute`, but consumes and returns the commit key
+    pub fn with_precomputation(mut self, window_size: usize) -> CommitKeyLagrange {
+        self.precompute(window_size);
+        self
+    }
+
+    /// Returns the window size of the precomputed tables, or `None` if `precompute` has not been called
+    pub fn precomputed_window_size(&self) -> Option<usize> {
+        self.precomputed.as_ref().map(FixedBaseMsm::window_size)
     }
 
     /// Commit to `polynomial` in lagrange form
     pub fn commit(&self, polynomial: &Polynomial) -> blstrs::G1Affine {
-        g1_lincomb(&self.inner, &polynomial.evaluations)
+        match &self.precomputed {
+            Some(precomputed) => precomputed.msm(&polynomial.evaluations).into(),
+            None => g1_lincomb(&self.inner, &polynomial.evaluations),
+        }
     }
 
     /// Returns the maximum degree polynomial that one can commit to
@@ -54,21 +109,52 @@ impl CommitKeyLagrange {
 }
 
 // A multi-scalar multiplication
+//
+// Panics, if the number of points and scalars differ. See `try_g1_lincomb` for a non-panicking version
 pub fn g1_lincomb(points: &[blstrs::G1Affine], scalars: &[blstrs::Scalar]) -> blstrs::G1Affine {
+    try_g1_lincomb(points, scalars).unwrap_or_else(|err| panic!("{err}"))
+}
+
+// A multi-scalar multiplication, which returns an error if the number of points and scalars differ
+pub fn try_g1_lincomb(
+    points: &[blstrs::G1Affine],
+    scalars: &[blstrs::Scalar],
+) -> Result<blstrs::G1Affine, KzgError> {
     // TODO: We could use arkworks here and use their parallelized multi-exp instead
 
-    // TODO: Spec says we should panic, but as a lib its better to return result
-    assert_eq!(points.len(), scalars.len());
+    // Spec says we should panic, but as a lib its better to return result
+    if points.len() != scalars.len() {
+        return Err(KzgError::LengthMismatch { expected: points.len(), actual: scalars.len() });
+    }
+
+    // blst does not use multiple threads, so we split the points into one chunk per thread
+    // and sum the results
+    #[cfg(feature = "parallel")]
+    {
+        use group::Group;
+
+        let chunk_size = crate::utils::parallel_chunk_size(points.len());
+        let result = points
+            .par_chunks(chunk_size)
+            .zip(scalars.par_chunks(chunk_size))
+            .map(|(points, scalars)| serial_multi_exp(points, scalars))
+            .reduce(blstrs::G1Projective::identity, |a, b| a + b);
+        Ok(result.into())
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    Ok(serial_multi_exp(points, scalars).into())
+}
 
-    let points_iter = points.into_iter();
+fn serial_multi_exp(points: &[blstrs::G1Affine], scalars: &[blstrs::Scalar]) -> blstrs::G1Projective {
+    let points_iter = points.iter();
 
     let points: Vec<_> = points_iter
-        .map(|point| blstrs::G1Projective::from(point))
+        .map(blstrs::G1Projective::from)
         .collect();
 
-    // blst does not use multiple threads
     // TODO: the internal lib seems to be converting back to Affine
-    blstrs::G1Projective::multi_exp(&points, scalars).into()
+    blstrs::G1Projective::multi_exp(&points, scalars)
 }
 
 #[cfg(test)]
@@ -76,14 +162,27 @@ mod tests {
     use ff::Field;
     use group::prime::PrimeCurveAffine;
 
-    use crate::{domain::Domain, commit_key::*};
+    use crate::{
+        domain::Domain, commit_key::*, error::KzgError, polynomial::Polynomial, polynomial_coeff::PolynomialCoeff,
+    };
 
-    fn eval_coeff_poly(poly: &[blstrs::Scalar], input_point: &blstrs::Scalar) -> blstrs::Scalar {
-        let mut result = blstrs::Scalar::zero();
-        for (index, coeff) in poly.iter().enumerate() {
-            result += input_point.pow_vartime(&[index as u64]) * coeff;
-        }
-        result
+    #[test]
+    fn invalid_inputs_are_rejected() {
+        let generator = blstrs::G1Affine::generator();
+
+        assert!(matches!(CommitKey::try_new(vec![]), Err(KzgError::TooFewPoints { minimum: 1, actual: 0 })));
+        assert!(matches!(
+            CommitKeyLagrange::try_new(vec![generator]),
+            Err(KzgError::TooFewPoints { minimum: 2, actual: 1 })
+        ));
+        assert_eq!(
+            try_g1_lincomb(&[generator, generator], &[blstrs::Scalar::one()]),
+            Err(KzgError::LengthMismatch { expected: 2, actual: 1 })
+        );
+        assert_eq!(
+            Polynomial::try_new(vec![blstrs::Scalar::one(); 3]),
+            Err(KzgError::NotPowerOfTwo(3))
+        );
     }
 
     #[test]
@@ -93,25 +192,25 @@ mod tests {
         let domain = Domain::new(degree);
 
         // f(x) -- These are the coefficients of the polynomial
-        let f_x_coeffs: Vec<_> = (0..degree as u64).into_iter().map(blstrs::Scalar::from).collect();
+        let f_x = PolynomialCoeff::new((0..degree as u64).map(blstrs::Scalar::from).collect());
 
         // Evaluate f(x) over the domain -- To get the evaluation form of f(x)
         let f_x_evaluations: Vec<_> = domain
             .roots
             .iter()
-            .map(|root| eval_coeff_poly(&f_x_coeffs, root))
+            .map(|root| f_x.evaluate(*root))
             .collect();
 
         let secret = blstrs::Scalar::from(1234567u64);
         let monomial_srs: Vec<blstrs::G1Affine> = (0..degree)
             .map(|index| {
-                let secret_exp = secret.pow_vartime(&[index as u64]);
+                let secret_exp = secret.pow_vartime([index as u64]);
                 (blstrs::G1Affine::generator() * secret_exp).into()
             })
             .collect();
 
         // Commit to f(x) in monomial form
-        let expected_commitment = g1_lincomb(&monomial_srs, &f_x_coeffs);
+        let expected_commitment = g1_lincomb(&monomial_srs, &f_x.coeffs);
 
         // Commit to f(x) in lagrange form
         let commit_key = CommitKey { inner: monomial_srs, };
@@ -120,4 +219,30 @@ mod tests {
 
         assert_eq!(expected_commitment, got_commitment)
     }
+
+    #[test]
+    fn precomputed_commit_matches_commit() {
+        let degree = 16;
+
+        let secret = blstrs::Scalar::from(1234567u64);
+        let monomial_srs: Vec<blstrs::G1Affine> = (0..degree)
+            .map(|index| (blstrs::G1Affine::generator() * secret.pow_vartime([index as u64])).into())
+            .collect();
+
+        let mut evaluations: Vec<_> = (0..degree - 2).map(|_| blstrs::Scalar::random(&mut rand::thread_rng())).collect();
+        evaluations.extend([blstrs::Scalar::zero(), -blstrs::Scalar::one()]);
+        let polynomial = Polynomial::new(evaluations);
+
+        for domain in [Domain::new(degree), Domain::new_bit_reversed(degree)] {
+            let mut commit_key = CommitKey::new(monomial_srs.clone()).into_lagrange(&domain);
+            assert_eq!(commit_key.precomputed_window_size(), None);
+            let expected_commitment = commit_key.commit(&polynomial);
+
+            for window_size in [1, 4, 8] {
+                commit_key.precompute(window_size);
+                assert_eq!(commit_key.precomputed_window_size(), Some(window_size));
+                assert_eq!(commit_key.commit(&polynomial), expected_commitment);
+            }
+        }
+    }
 }
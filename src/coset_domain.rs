@@ -0,0 +1,176 @@
+use ff::{Field, PrimeField};
+
+use crate::{
+    domain::Domain, error::KzgError, polynomial::Polynomial, polynomial_coeff::PolynomialCoeff, utils,
+};
+
+/// The coset `shift * roots()` of a domain
+///
+/// Polynomials over a coset are stored as their evaluations at `shift * domain.roots()[i]`, so the
+/// evaluations are in the same order as for the domain. Unlike the domain, a coset does not contain
+/// the roots of unity when `shift^n != 1`, which is useful to evaluate quotients without dividing by zero.
+#[derive(Debug, Clone)]
+pub struct CosetDomain {
+    domain: Domain,
+    shift: blstrs::Scalar,
+    // `shift^n`, where `n` is the size of the domain
+    shift_pow_n: blstrs::Scalar,
+    // The points of the coset, in the same order as `domain.roots()`
+    roots: Vec<blstrs::Scalar>,
+    // `1 / \prod_{j != i} (roots_i - roots_j)`, which are used to evaluate polynomials outside of the coset
+    barycentric_weights: Vec<blstrs::Scalar>,
+}
+
+impl CosetDomain {
+    /// Panics, if `shift` is zero. See `try_new` for a non-panicking version
+    pub fn new(domain: Domain, shift: blstrs::Scalar) -> CosetDomain {
+        CosetDomain::try_new(domain, shift).unwrap_or_else(|err| panic!("cannot initialize `CosetDomain`: {err}"))
+    }
+
+    pub fn try_new(domain: Domain, shift: blstrs::Scalar) -> Result<CosetDomain, KzgError> {
+        if bool::from(shift.is_zero()) {
+            return Err(KzgError::ZeroInversion);
+        }
+
+        let shift_pow_n = shift.pow_vartime([domain.size() as u64]);
+        let roots: Vec<_> = domain.roots().iter().map(|root| shift * root).collect();
+
+        // The derivative of the vanishing polynomial `X^n - shift^n` at a point `x` of the coset is
+        // `n * x^{n - 1} = n * shift^n / x`, so the weights are `x / (n * shift^n)`
+        let weight_factor = (domain.domain_size * shift_pow_n).invert().unwrap();
+        let barycentric_weights = roots.iter().map(|root| root * weight_factor).collect();
+
+        Ok(CosetDomain { domain, shift, shift_pow_n, roots, barycentric_weights })
+    }
+
+    /// Uses the multiplicative generator of the field as the shift, which is not in any subgroup of order
+    /// `2^k`, so the coset never intersects a domain
+    pub fn with_default_shift(domain: Domain) -> CosetDomain {
+        CosetDomain::new(domain, blstrs::Scalar::multiplicative_generator())
+    }
+
+    pub fn domain(&self) -> &Domain {
+        &self.domain
+    }
+
+    pub fn shift(&self) -> blstrs::Scalar {
+        self.shift
+    }
+
+    pub fn size(&self) -> usize {
+        self.domain.size()
+    }
+
+    /// Returns the points of the coset in the order that the evaluations of a polynomial are stored
+    pub fn roots(&self) -> &[blstrs::Scalar] {
+        &self.roots
+    }
+
+    pub fn barycentric_weights(&self) -> &[blstrs::Scalar] {
+        &self.barycentric_weights
+    }
+
+    /// Returns `X^n - shift^n`, which vanishes on the coset
+    pub fn vanishing_polynomial(&self) -> PolynomialCoeff {
+        let mut coeffs = vec![blstrs::Scalar::zero(); self.size() + 1];
+        coeffs[0] = -self.shift_pow_n;
+        coeffs[self.size()] = blstrs::Scalar::one();
+        PolynomialCoeff::new(coeffs)
+    }
+
+    pub fn evaluate_vanishing_polynomial(&self, z: blstrs::Scalar) -> blstrs::Scalar {
+        z.pow_vartime([self.size() as u64]) - self.shift_pow_n
+    }
+
+    /// See `Domain::coset_fft_scalars`
+    pub fn fft_scalars(&self, coeffs: Vec<blstrs::Scalar>) -> Vec<blstrs::Scalar> {
+        self.domain.coset_fft_scalars(coeffs, self.shift)
+    }
+
+    /// See `Domain::coset_ifft_scalars`
+    pub fn ifft_scalars(&self, evaluations: Vec<blstrs::Scalar>) -> Vec<blstrs::Scalar> {
+        self.domain.coset_ifft_scalars(evaluations, self.shift)
+    }
+
+    /// See `Domain::coset_fft_g1`
+    pub fn fft_g1(&self, points: Vec<blstrs::G1Affine>) -> Vec<blstrs::G1Affine> {
+        self.domain.coset_fft_g1(points, self.shift)
+    }
+
+    /// See `Domain::coset_ifft_g1`
+    pub fn ifft_g1(&self, points: Vec<blstrs::G1Affine>) -> Vec<blstrs::G1Affine> {
+        self.domain.coset_ifft_g1(points, self.shift)
+    }
+
+    /// Evaluates `polynomial`, whose evaluations are over the coset, at `z`
+    ///
+    /// Outside of the coset, this uses the barycentric formula `Z(z) * \sum w_i * p_i / (z - x_i)`.
+    pub fn evaluate(&self, polynomial: &Polynomial, z: blstrs::Scalar) -> blstrs::Scalar {
+        assert_eq!(
+            polynomial.evaluations.len(),
+            self.size(),
+            "the size of the coset being used != the domain size of the polynomial"
+        );
+
+        if let Some(index) = self.roots.iter().position(|root| *root == z) {
+            return polynomial.evaluations[index];
+        }
+
+        let mut denominators: Vec<_> = self.roots.iter().map(|root| z - root).collect();
+        utils::batch_inversion(&mut denominators);
+
+        let result: blstrs::Scalar = polynomial
+            .evaluations
+            .iter()
+            .zip(&self.barycentric_weights)
+            .zip(&denominators)
+            .map(|((evaluation, weight), denominator_inv)| evaluation * weight * denominator_inv)
+            .sum();
+
+        result * self.evaluate_vanishing_polynomial(z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coset_domain() {
+        let size = 16;
+        let coeffs: Vec<_> = (0..size).map(|_| blstrs::Scalar::random(&mut rand::thread_rng())).collect();
+        let poly_coeff = PolynomialCoeff::new(coeffs.clone());
+
+        for domain in [Domain::new(size), Domain::new_bit_reversed(size)] {
+            let coset_domain = CosetDomain::with_default_shift(domain);
+
+            // The roots are the coset, and the vanishing polynomial is zero on it but not on the domain
+            for (root, coset_root) in coset_domain.domain().roots().iter().zip(coset_domain.roots()) {
+                assert_eq!(*coset_root, coset_domain.shift() * root);
+                assert!(coset_domain.vanishing_polynomial().evaluate(*coset_root).is_zero_vartime());
+                assert!(!coset_domain.evaluate_vanishing_polynomial(*root).is_zero_vartime());
+            }
+
+            // The weights are `1 / \prod_{j != i} (x_i - x_j)`
+            let roots = coset_domain.roots();
+            for (i, weight) in coset_domain.barycentric_weights().iter().enumerate() {
+                let product: blstrs::Scalar =
+                    roots.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, root)| roots[i] - root).product();
+                assert_eq!(*weight * product, blstrs::Scalar::one());
+            }
+
+            let polynomial = Polynomial::new(coset_domain.fft_scalars(coeffs.clone()));
+            assert_eq!(coset_domain.ifft_scalars(polynomial.evaluations.clone()), coeffs);
+
+            // Evaluate inside and outside of the coset
+            let z = blstrs::Scalar::random(&mut rand::thread_rng());
+            assert_eq!(coset_domain.evaluate(&polynomial, z), poly_coeff.evaluate(z));
+            assert_eq!(coset_domain.evaluate(&polynomial, roots[3]), polynomial.evaluations[3]);
+        }
+
+        assert!(matches!(
+            CosetDomain::try_new(Domain::new(size), blstrs::Scalar::zero()),
+            Err(KzgError::ZeroInversion)
+        ));
+    }
+}
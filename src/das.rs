@@ -0,0 +1,317 @@
+use ff::{Field, PrimeField};
+
+use crate::{
+    domain::Domain,
+    eip4844::{blob_to_polynomial, Blob},
+    error::KzgError,
+    polynomial::Polynomial,
+    polynomial_coeff::PolynomialCoeff,
+    utils::batch_inversion,
+};
+
+// The ratio of the size of the extended data to the size of the original data
+pub const EXTENSION_FACTOR: usize = 2;
+
+// Below this many points, the vanishing polynomial is computed directly instead of with FFTs
+const VANISHING_POLYNOMIAL_FFT_THRESHOLD: usize = 64;
+
+/// Reed-Solomon extends `polynomial` by evaluating it over `extended_domain`, which is larger than `domain`
+///
+/// The polynomial is interpolated over `domain` and then evaluated over `extended_domain`, so the
+/// extended polynomial is the same polynomial, with more evaluations. Any `domain.size()` of the
+/// extended evaluations are enough to recover it.
+///
+/// The roots of `domain` are also roots of `extended_domain`, so the original evaluations are part of the
+/// extended evaluations. With bit-reversed domains, they are the first `domain.size()` evaluations, and
+/// with natural order domains, they are every `extended_domain.size() / domain.size()`'th evaluation.
+///
+/// Panics, if the polynomial does not have `domain.size()` evaluations, if `extended_domain` is not larger
+/// than `domain`, or if the domains do not store their evaluations in the same order
+pub fn extend_polynomial(polynomial: &Polynomial, domain: &Domain, extended_domain: &Domain) -> Polynomial {
+    assert!(
+        extended_domain.size() > domain.size(),
+        "the extended domain size {} must be larger than the domain size {}",
+        extended_domain.size(),
+        domain.size()
+    );
+    assert_eq!(
+        domain.is_bit_reversed(),
+        extended_domain.is_bit_reversed(),
+        "both domains must store their evaluations in the same order"
+    );
+
+    let poly_coeff = PolynomialCoeff::from_polynomial(polynomial, domain);
+    Polynomial::new(extended_domain.fft_scalars(poly_coeff.coeffs))
+}
+
+/// Doubles the data in `blob` by extending its polynomial over `extended_domain`
+///
+/// Blobs are stored in bit-reversed order, so both domains should be created with `Domain::new_bit_reversed`,
+/// and `extended_domain` should be `EXTENSION_FACTOR` times as large as `domain`.
+/// The first half of the extended evaluations is then the original blob.
+pub fn extend_blob(blob: &Blob, domain: &Domain, extended_domain: &Domain) -> Result<Polynomial, KzgError> {
+    let polynomial = blob_to_polynomial(blob)?;
+    Ok(extend_polynomial(&polynomial, domain, extended_domain))
+}
+
+/// Recovers the extended polynomial from `evaluations` at `indices`, which must be at least half of them
+///
+/// `indices` refer to the evaluations of the extended polynomial, in the same order as `extended_domain.roots()`,
+/// and the extended polynomial is the result of `extend_polynomial` with `domain` and `extended_domain`.
+///
+/// Let `E(X)` be the extended polynomial with the missing evaluations replaced by zero, `Z(X)` be the
+/// polynomial that vanishes at the roots of the missing evaluations, and `D(X)` be the polynomial that we want
+/// to recover. Then `E(X)Z(X) = D(X)Z(X)` at every root of `extended_domain`, and since `D(X)Z(X)` has degree
+/// less than `extended_domain.size()`, we can interpolate it. `D(X)` is then recovered by dividing by `Z(X)`
+/// on a coset of `extended_domain`, where `Z(X)` has no zeroes.
+///
+/// Returns an error, if there are fewer than `domain.size()` samples, an index is out of range or repeated,
+/// or if the samples are not evaluations of a polynomial of degree less than `domain.size()`
+pub fn recover_polynomial(
+    indices: &[usize],
+    evaluations: &[blstrs::Scalar],
+    domain: &Domain,
+    extended_domain: &Domain,
+) -> Result<Polynomial, KzgError> {
+    if indices.len() != evaluations.len() {
+        return Err(KzgError::LengthMismatch { expected: indices.len(), actual: evaluations.len() });
+    }
+    if indices.len() < domain.size() {
+        return Err(KzgError::TooFewPoints { minimum: domain.size(), actual: indices.len() });
+    }
+
+    let extended_size = extended_domain.size();
+    let mut extended_evaluations = vec![blstrs::Scalar::zero(); extended_size];
+    let mut is_known = vec![false; extended_size];
+    for (&index, evaluation) in indices.iter().zip(evaluations) {
+        if index >= extended_size {
+            return Err(KzgError::IndexOutOfRange { index, size: extended_size });
+        }
+        if is_known[index] {
+            return Err(KzgError::DuplicateIndex(index));
+        }
+        is_known[index] = true;
+        extended_evaluations[index] = *evaluation;
+    }
+
+    let missing_roots: Vec<_> = extended_domain
+        .roots()
+        .iter()
+        .zip(&is_known)
+        .filter(|(_, is_known)| !**is_known)
+        .map(|(root, _)| *root)
+        .collect();
+
+    // Z(X), in coefficient form and evaluated over the domain
+    let zero_poly = vanishing_polynomial(&missing_roots);
+    let zero_poly_evaluations = extended_domain.fft_scalars(zero_poly.coeffs.clone());
+
+    // (E * Z)(X), where the missing evaluations are zero, since they are roots of Z(X)
+    let extended_times_zero: Vec<_> = extended_evaluations
+        .iter()
+        .zip(&zero_poly_evaluations)
+        .map(|(evaluation, zero_poly_evaluation)| evaluation * zero_poly_evaluation)
+        .collect();
+    let extended_times_zero = extended_domain.ifft_scalars(extended_times_zero);
+
+    // Divide by Z(X) on a coset, since Z(X) is zero at some of the roots of the domain.
+    // The multiplicative generator of the field is not in any subgroup of order 2^k, so Z(X) has no zeroes on the coset
+    let coset_shift = blstrs::Scalar::multiplicative_generator();
+    let extended_times_zero_coset = extended_domain.coset_fft_scalars(extended_times_zero, coset_shift);
+    let mut zero_poly_coset = extended_domain.coset_fft_scalars(zero_poly.coeffs, coset_shift);
+    batch_inversion(&mut zero_poly_coset);
+
+    let quotient_coset: Vec<_> = extended_times_zero_coset
+        .iter()
+        .zip(&zero_poly_coset)
+        .map(|(numerator, denominator_inv)| numerator * denominator_inv)
+        .collect();
+    let mut recovered = extended_domain.coset_ifft_scalars(quotient_coset, coset_shift);
+
+    // The quotient only has degree less than the original domain size if the samples are consistent
+    if recovered[domain.size()..].iter().any(|coeff| !coeff.is_zero_vartime()) {
+        return Err(KzgError::InconsistentSamples);
+    }
+    recovered.truncate(domain.size());
+
+    Ok(Polynomial::new(extended_domain.fft_scalars(recovered)))
+}
+
+// Computes `\prod (X - point_i)`
+//
+// The points are split in half recursively, and the halves are multiplied using FFTs
+fn vanishing_polynomial(points: &[blstrs::Scalar]) -> PolynomialCoeff {
+    if points.len() <= VANISHING_POLYNOMIAL_FFT_THRESHOLD {
+        return PolynomialCoeff::vanishing(points);
+    }
+
+    let (left, right) = points.split_at(points.len() / 2);
+    let left = vanishing_polynomial(left);
+    let right = vanishing_polynomial(right);
+
+    let num_coeffs = left.coeffs.len() + right.coeffs.len() - 1;
+    let domain = Domain::new(num_coeffs);
+    let product: Vec<_> = domain
+        .fft_scalars(left.coeffs)
+        .iter()
+        .zip(domain.fft_scalars(right.coeffs))
+        .map(|(left, right)| left * right)
+        .collect();
+
+    let mut coeffs = domain.ifft_scalars(product);
+    coeffs.truncate(num_coeffs);
+    PolynomialCoeff::new(coeffs)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::seq::SliceRandom;
+
+    use super::*;
+    use crate::{eip4844::FIELD_ELEMENTS_PER_BLOB, params::PublicParameters};
+
+    fn random_polynomial(size: usize) -> Polynomial {
+        Polynomial::new((0..size).map(|_| blstrs::Scalar::random(&mut rand::thread_rng())).collect())
+    }
+
+    #[test]
+    fn extension_contains_original_evaluations() {
+        let size = 16;
+        let polynomial = random_polynomial(size);
+        let z = blstrs::Scalar::random(&mut rand::thread_rng());
+
+        let domain = Domain::new(size);
+        let extended_domain = Domain::new(EXTENSION_FACTOR * size);
+        let extended = extend_polynomial(&polynomial, &domain, &extended_domain);
+        let even_evaluations: Vec<_> = extended.evaluations.iter().step_by(EXTENSION_FACTOR).copied().collect();
+        assert_eq!(even_evaluations, polynomial.evaluations);
+        assert_eq!(extended.evaluate(z, &extended_domain), polynomial.evaluate(z, &domain));
+
+        let domain = Domain::new_bit_reversed(size);
+        let extended_domain = Domain::new_bit_reversed(EXTENSION_FACTOR * size);
+        let extended = extend_polynomial(&polynomial, &domain, &extended_domain);
+        assert_eq!(extended.evaluations[..size], polynomial.evaluations);
+        assert_eq!(extended.evaluate(z, &extended_domain), polynomial.evaluate(z, &domain));
+    }
+
+    #[test]
+    fn extend_blob_starts_with_blob() {
+        let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+        let extended_domain = Domain::new_bit_reversed(EXTENSION_FACTOR * FIELD_ELEMENTS_PER_BLOB);
+
+        let polynomial = random_polynomial(FIELD_ELEMENTS_PER_BLOB);
+        let blob: Vec<u8> = polynomial.evaluations.iter().flat_map(|evaluation| evaluation.to_bytes_be()).collect();
+        let blob: &Blob = blob.as_slice().try_into().unwrap();
+
+        let extended = extend_blob(blob, &domain, &extended_domain).unwrap();
+        assert_eq!(extended.evaluations.len(), EXTENSION_FACTOR * FIELD_ELEMENTS_PER_BLOB);
+        assert_eq!(extended.evaluations[..FIELD_ELEMENTS_PER_BLOB], polynomial.evaluations);
+    }
+
+    #[test]
+    fn recover_from_half_of_the_samples() {
+        let size = 16;
+        let polynomial = random_polynomial(size);
+
+        for (domain, extended_domain) in [
+            (Domain::new(size), Domain::new(EXTENSION_FACTOR * size)),
+            (Domain::new_bit_reversed(size), Domain::new_bit_reversed(EXTENSION_FACTOR * size)),
+        ] {
+            let extended = extend_polynomial(&polynomial, &domain, &extended_domain);
+
+            // Keep a random half of the samples
+            let mut indices: Vec<_> = (0..EXTENSION_FACTOR * size).collect();
+            indices.shuffle(&mut rand::thread_rng());
+            indices.truncate(size);
+            let evaluations: Vec<_> = indices.iter().map(|index| extended.evaluations[*index]).collect();
+
+            let recovered = recover_polynomial(&indices, &evaluations, &domain, &extended_domain).unwrap();
+            assert_eq!(recovered, extended);
+
+            // All of the samples can also be given
+            let all_indices: Vec<_> = (0..EXTENSION_FACTOR * size).collect();
+            let recovered = recover_polynomial(&all_indices, &extended.evaluations, &domain, &extended_domain);
+            assert_eq!(recovered, Ok(extended));
+        }
+    }
+
+    #[test]
+    fn recover_blob() {
+        let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+        let extended_domain = Domain::new_bit_reversed(EXTENSION_FACTOR * FIELD_ELEMENTS_PER_BLOB);
+
+        let polynomial = random_polynomial(FIELD_ELEMENTS_PER_BLOB);
+        let extended = extend_polynomial(&polynomial, &domain, &extended_domain);
+
+        // Only the extension is available, so none of the original data
+        let indices: Vec<_> = (FIELD_ELEMENTS_PER_BLOB..EXTENSION_FACTOR * FIELD_ELEMENTS_PER_BLOB).collect();
+        let evaluations = extended.evaluations[FIELD_ELEMENTS_PER_BLOB..].to_vec();
+
+        let recovered = recover_polynomial(&indices, &evaluations, &domain, &extended_domain).unwrap();
+        assert_eq!(recovered, extended);
+    }
+
+    #[test]
+    fn invalid_samples_are_rejected() {
+        let size = 16;
+        let domain = Domain::new(size);
+        let extended_domain = Domain::new(EXTENSION_FACTOR * size);
+        let extended = extend_polynomial(&random_polynomial(size), &domain, &extended_domain);
+
+        let indices: Vec<_> = (0..size + 2).collect();
+        let mut evaluations = extended.evaluations[..size + 2].to_vec();
+        let recover = |indices: &[usize], evaluations: &[blstrs::Scalar]| {
+            recover_polynomial(indices, evaluations, &domain, &extended_domain)
+        };
+
+        assert_eq!(
+            recover(&indices[..size - 1], &evaluations[..size - 1]),
+            Err(KzgError::TooFewPoints { minimum: size, actual: size - 1 })
+        );
+        assert_eq!(
+            recover(&indices, &evaluations[1..]),
+            Err(KzgError::LengthMismatch { expected: size + 2, actual: size + 1 })
+        );
+
+        let mut out_of_range = indices.clone();
+        out_of_range[3] = 2 * size;
+        assert_eq!(
+            recover(&out_of_range, &evaluations),
+            Err(KzgError::IndexOutOfRange { index: 2 * size, size: 2 * size })
+        );
+
+        let mut duplicate = indices.clone();
+        duplicate[3] = 2;
+        assert_eq!(recover(&duplicate, &evaluations), Err(KzgError::DuplicateIndex(2)));
+
+        // With more samples than needed, a wrong sample is detected
+        evaluations[5] += blstrs::Scalar::one();
+        assert_eq!(recover(&indices, &evaluations), Err(KzgError::InconsistentSamples));
+    }
+
+    #[test]
+    fn vanishing_polynomial_matches_direct_computation() {
+        let points: Vec<_> = (0..3 * VANISHING_POLYNOMIAL_FFT_THRESHOLD + 5)
+            .map(|_| blstrs::Scalar::random(&mut rand::thread_rng()))
+            .collect();
+        assert_eq!(vanishing_polynomial(&points), PolynomialCoeff::vanishing(&points));
+    }
+
+    #[test]
+    fn extension_has_the_same_commitment() {
+        let size = 16;
+        let domain = Domain::new_bit_reversed(size);
+        let extended_domain = Domain::new_bit_reversed(EXTENSION_FACTOR * size);
+
+        // The lagrange SRS for both domains is derived from the same secret
+        let public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+        let extended_public_parameters = PublicParameters::from_secret_insecure(123456789, &extended_domain);
+
+        let polynomial = random_polynomial(size);
+        let extended = extend_polynomial(&polynomial, &domain, &extended_domain);
+        assert_eq!(
+            extended_public_parameters.commit_key.commit(&extended),
+            public_parameters.commit_key.commit(&polynomial)
+        );
+    }
+}
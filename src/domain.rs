@@ -1,11 +1,20 @@
 
+use std::ops::{Add, Mul, Sub};
+
 use group::{prime::PrimeCurveAffine, Curve, Group};
 use ff::{Field, PrimeField};
 
+use crate::{error::KzgError, utils::{bit_reversal_permutation, compute_powers, reverse_bits}};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 #[derive(Debug, Clone)]
 pub struct Domain {
-    // roots of unity
+    // roots of unity in natural order
     pub roots: Vec<blstrs::Scalar>,
+    // roots of unity in bit-reversed order
+    pub roots_brp: Vec<blstrs::Scalar>,
     // Domain size as a scalar
     pub domain_size: blstrs::Scalar,
     // Inverse of the domain size as a scalar
@@ -16,20 +25,30 @@ pub struct Domain {
     // Inverse of the generator
     // This is useful for IFFT
     pub generator_inv: blstrs::Scalar,
+    // Whether polynomials over this domain store their evaluations in bit-reversed order
+    bit_reversed: bool,
+    // The first `domain_size / 2` powers of the generator, used by the FFT
+    twiddle_factors: Vec<blstrs::Scalar>,
+    // The first `domain_size / 2` powers of the inverse of the generator, used by the IFFT
+    twiddle_factors_inv: Vec<blstrs::Scalar>,
 }
 
 impl Domain {
+    /// Panics, if there are no roots of unity of order `size`. See `try_new` for a non-panicking version
     pub fn new(size: usize) -> Domain {
+        Domain::try_new(size).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_new(size: usize) -> Result<Domain, KzgError> {
         // We are using roots of unity, so the
         // size of the domain will be padded to
-        // the next power of two
-        let size = if size.is_power_of_two() {
-            size
-        } else {
-            size.next_power_of_two()
-        };
+        // the next power of two, which must fit in a `usize`
+        let size = size.checked_next_power_of_two().ok_or(KzgError::DomainTooLarge {
+            log_size: usize::BITS,
+            two_adicity: Domain::two_adicity(),
+        })?;
 
-        let generator = Self::compute_generator_for_size(size);
+        let generator = Self::try_compute_generator_for_size(size)?;
         let generator_inv = generator.invert().unwrap(); // Generator should not be zero
 
         let size_as_scalar = blstrs::Scalar::from(size as u64);
@@ -43,13 +62,38 @@ impl Domain {
             roots.push(prev_root * generator)
         }
 
-        Self {
+        let roots_brp = bit_reversal_permutation(&roots);
+
+        // The inverse of `generator^k` is `generator^(size - k)`
+        let twiddle_factors = roots[..size / 2].to_vec();
+        let twiddle_factors_inv = (0..size / 2).map(|k| roots[(size - k) % size]).collect();
+
+        Ok(Self {
             roots,
+            roots_brp,
             domain_size: size_as_scalar,
             domain_size_inv: size_as_scalar_inv,
             generator,
             generator_inv,
-        }
+            bit_reversed: false,
+            twiddle_factors,
+            twiddle_factors_inv,
+        })
+    }
+
+    /// Creates a domain where the evaluations of a polynomial are stored in bit-reversed order.
+    ///
+    /// This is the order used by the Ethereum trusted setup and blobs.
+    pub fn new_bit_reversed(size: usize) -> Domain {
+        Domain { bit_reversed: true, ..Domain::new(size) }
+    }
+
+    pub fn try_new_bit_reversed(size: usize) -> Result<Domain, KzgError> {
+        Ok(Domain { bit_reversed: true, ..Domain::try_new(size)? })
+    }
+
+    pub fn is_bit_reversed(&self) -> bool {
+        self.bit_reversed
     }
 
     fn largest_root_of_unity() -> blstrs::Scalar {
@@ -59,18 +103,23 @@ impl Domain {
         .unwrap()
     }
 
-    fn compute_generator_for_size(size: usize) -> blstrs::Scalar {
-        assert!(size.is_power_of_two());
+    fn try_compute_generator_for_size(size: usize) -> Result<blstrs::Scalar, KzgError> {
+        if !size.is_power_of_two() {
+            return Err(KzgError::NotPowerOfTwo(size));
+        }
 
         let log_size_of_group = size.trailing_zeros();
         if log_size_of_group > Domain::two_adicity() {
-            panic!("two adicity is 32 but group size needed is 2^{log_size_of_group}");
+            return Err(KzgError::DomainTooLarge {
+                log_size: log_size_of_group,
+                two_adicity: Domain::two_adicity(),
+            });
         }
 
         // We now want to compute the generator which has order `size`
         let exponent: u64 = 1 << (Domain::two_adicity() as u64 - log_size_of_group as u64);
 
-        Domain::largest_root_of_unity().pow_vartime(&[exponent])
+        Ok(Domain::largest_root_of_unity().pow_vartime([exponent]))
     }
 
     const fn two_adicity() -> u32 {
@@ -82,13 +131,26 @@ impl Domain {
     }
 
     pub fn find(&self, element: &blstrs::Scalar) -> Option<usize> {
-        self.roots.iter().position(|root_i| root_i == element)
+        self.roots().iter().position(|root_i| root_i == element)
     }
 
+    /// Returns the roots of unity in the order that the evaluations of a polynomial are stored.
+    ///
+    /// ie. evaluation `i` of a polynomial is its value at `roots()[i]`
     pub fn roots(&self) -> &[blstrs::Scalar] {
-        &self.roots
+        if self.bit_reversed {
+            &self.roots_brp
+        } else {
+            &self.roots
+        }
     }
 
+    pub fn roots_brp(&self) -> &[blstrs::Scalar] {
+        &self.roots_brp
+    }
+
+    /// Panics, if the number of points is not equal to the domain size.
+    /// See `try_ifft_g1` for a non-panicking version
     pub fn ifft_g1(&self, points: Vec<blstrs::G1Affine>) -> Vec<blstrs::G1Affine> {
         if points.len() != self.size() {
             panic!(
@@ -97,73 +159,302 @@ impl Domain {
                 self.size()
             )
         }
+        self.ifft_g1_unchecked(points)
+    }
 
-        let points_proj: Vec<_> = points
+    pub fn try_ifft_g1(&self, points: Vec<blstrs::G1Affine>) -> Result<Vec<blstrs::G1Affine>, KzgError> {
+        if points.len() != self.size() {
+            return Err(KzgError::LengthMismatch { expected: self.size(), actual: points.len() });
+        }
+        Ok(self.ifft_g1_unchecked(points))
+    }
+
+    fn ifft_g1_unchecked(&self, points: Vec<blstrs::G1Affine>) -> Vec<blstrs::G1Affine> {
+        let mut ifft_g1: Vec<_> = points
             .into_iter()
-            .map(|point_aff| blstrs::G1Projective::from(point_aff))
+            .map(blstrs::G1Projective::from)
             .collect();
 
-        let mut ifft_g1 = fft_g1(self.generator_inv, &points_proj);
+        fft_in_place(&mut ifft_g1, &self.twiddle_factors_inv);
 
         for element in ifft_g1.iter_mut() {
-            *element = *element * self.domain_size_inv
+            *element *= self.domain_size_inv
         }
 
-        let mut affine = vec![blstrs::G1Affine::identity(); ifft_g1.len()];
-        blstrs::G1Projective::batch_normalize(&ifft_g1, &mut affine);
-        return affine;
+        batch_normalize(&ifft_g1)
     }
+
+    /// Evaluates the polynomial with G1 coefficients `points` over the domain.
+    ///
+    /// The evaluations are returned in the same order as `roots()`.
+    /// If there are fewer points than the domain size, the rest are assumed to be the identity.
+    pub fn fft_g1(&self, points: Vec<blstrs::G1Affine>) -> Vec<blstrs::G1Affine> {
+        if points.len() > self.size() {
+            panic!(
+                "number of points {}, must not exceed the domain size {}",
+                points.len(),
+                self.size()
+            )
+        }
+
+        let mut fft_g1: Vec<_> = points.into_iter().map(blstrs::G1Projective::from).collect();
+        fft_g1.resize(self.size(), blstrs::G1Projective::identity());
+
+        fft_in_place(&mut fft_g1, &self.twiddle_factors);
+
+        let affine = batch_normalize(&fft_g1);
+        if self.bit_reversed {
+            bit_reversal_permutation(&affine)
+        } else {
+            affine
+        }
+    }
+
+    /// Evaluates the polynomial with coefficients `coeffs` over the domain.
+    ///
+    /// The evaluations are returned in the same order as `roots()`.
+    /// If there are fewer coefficients than the domain size, the rest are assumed to be zero.
+    pub fn fft_scalars(&self, mut coeffs: Vec<blstrs::Scalar>) -> Vec<blstrs::Scalar> {
+        if coeffs.len() > self.size() {
+            panic!(
+                "number of coefficients {}, must not exceed the domain size {}",
+                coeffs.len(),
+                self.size()
+            )
+        }
+        coeffs.resize(self.size(), blstrs::Scalar::zero());
+
+        fft_in_place(&mut coeffs, &self.twiddle_factors);
+        if self.bit_reversed {
+            bit_reversal_permutation(&coeffs)
+        } else {
+            coeffs
+        }
+    }
+
+    /// Interpolates the coefficients of the polynomial with evaluations `evaluations` over the domain.
+    ///
+    /// The evaluations must be in the same order as `roots()`.
+    pub fn ifft_scalars(&self, evaluations: Vec<blstrs::Scalar>) -> Vec<blstrs::Scalar> {
+        if evaluations.len() != self.size() {
+            panic!(
+                "number of evaluations {}, must equal the domain size {}",
+                evaluations.len(),
+                self.size()
+            )
+        }
+
+        let mut coeffs = if self.bit_reversed {
+            bit_reversal_permutation(&evaluations)
+        } else {
+            evaluations
+        };
+
+        fft_in_place(&mut coeffs, &self.twiddle_factors_inv);
+        for coeff in coeffs.iter_mut() {
+            *coeff *= self.domain_size_inv
+        }
+        coeffs
+    }
+
+    /// Evaluates the polynomial with coefficients `coeffs` over the coset `shift * roots()`.
+    ///
+    /// This is the same as evaluating `p(shift * X)` over the domain, so the evaluations are returned in the
+    /// same order as `roots()`. If there are fewer coefficients than the domain size, the rest are assumed to be zero.
+    pub fn coset_fft_scalars(&self, coeffs: Vec<blstrs::Scalar>, shift: blstrs::Scalar) -> Vec<blstrs::Scalar> {
+        let shift_powers = compute_powers(shift, coeffs.len());
+        let coeffs = coeffs.iter().zip(shift_powers).map(|(coeff, shift_power)| coeff * shift_power).collect();
+        self.fft_scalars(coeffs)
+    }
+
+    /// Interpolates the coefficients of the polynomial with evaluations `evaluations` over the coset `shift * roots()`.
+    ///
+    /// The evaluations must be in the same order as `roots()`. Panics, if `shift` is zero
+    pub fn coset_ifft_scalars(&self, evaluations: Vec<blstrs::Scalar>, shift: blstrs::Scalar) -> Vec<blstrs::Scalar> {
+        let coeffs = self.ifft_scalars(evaluations);
+        let shift_inv_powers = compute_powers(shift.invert().expect("the coset shift cannot be zero"), coeffs.len());
+        coeffs.iter().zip(shift_inv_powers).map(|(coeff, shift_inv_power)| coeff * shift_inv_power).collect()
+    }
+
+    /// Evaluates the polynomial with G1 coefficients `points` over the coset `shift * roots()`.
+    ///
+    /// The evaluations are returned in the same order as `roots()`.
+    /// If there are fewer points than the domain size, the rest are assumed to be the identity.
+    pub fn coset_fft_g1(&self, points: Vec<blstrs::G1Affine>, shift: blstrs::Scalar) -> Vec<blstrs::G1Affine> {
+        let shift_powers = compute_powers(shift, points.len());
+        let points: Vec<_> = points.iter().zip(shift_powers).map(|(point, shift_power)| point * shift_power).collect();
+        self.fft_g1(batch_normalize(&points))
+    }
+
+    /// Interpolates the G1 coefficients of the polynomial with evaluations `points` over the coset `shift * roots()`.
+    ///
+    /// Unlike `ifft_g1`, the evaluations must be in the same order as `roots()`, so this is the inverse of `coset_fft_g1`.
+    /// Panics, if `shift` is zero or the number of points is not equal to the domain size
+    pub fn coset_ifft_g1(&self, points: Vec<blstrs::G1Affine>, shift: blstrs::Scalar) -> Vec<blstrs::G1Affine> {
+        let points = if self.bit_reversed { bit_reversal_permutation(&points) } else { points };
+        let coeffs = self.ifft_g1(points);
+
+        let shift_inv_powers = compute_powers(shift.invert().expect("the coset shift cannot be zero"), coeffs.len());
+        let coeffs: Vec<_> =
+            coeffs.iter().zip(shift_inv_powers).map(|(coeff, shift_inv_power)| coeff * shift_inv_power).collect();
+        batch_normalize(&coeffs)
+    }
+}
+
+fn batch_normalize(points: &[blstrs::G1Projective]) -> Vec<blstrs::G1Affine> {
+    let mut affine = vec![blstrs::G1Affine::identity(); points.len()];
+    blstrs::G1Projective::batch_normalize(points, &mut affine);
+    affine
 }
 
 impl std::ops::Index<usize> for &Domain {
     type Output = blstrs::Scalar;
 
     fn index(&self, i: usize) -> &Self::Output {
-        &self.roots[i]
+        &self.roots()[i]
     }
 }
 
-fn fft_g1(nth_root_of_unity: blstrs::Scalar, points: &[blstrs::G1Projective]) -> Vec<blstrs::G1Projective> {
-    let n = points.len();
-    if n == 1 {
-        return points.to_vec();
+// Iterative, in-place radix-2 Cooley-Tukey FFT over any group, where the scalars are from the BLS scalar field.
+//
+// This is used for both scalars and G1 points.
+// `twiddle_factors` must hold the first `values.len() / 2` powers of a root of unity of order `values.len()`,
+// and the output is the evaluations at the powers of that root, in natural order.
+//
+// With the `parallel` feature, the butterflies in each layer are computed in parallel.
+// Each butterfly does the same operations either way, so the output is identical.
+fn fft_in_place<T>(values: &mut [T], twiddle_factors: &[blstrs::Scalar])
+where
+    T: Copy + Send + Sync + Add<Output = T> + Sub<Output = T> + Mul<blstrs::Scalar, Output = T>,
+{
+    let n = values.len();
+    assert_eq!(twiddle_factors.len(), n / 2, "the number of twiddle factors must be half the number of values");
+
+    // The butterflies below take the input in bit-reversed order and produce the output in natural order
+    for i in 0..n {
+        let j = reverse_bits(i, n);
+        if i < j {
+            values.swap(i, j);
+        }
     }
 
-    let (even, odd) = take_even_odd(points);
+    // Combine pairs of FFTs of size `half_size` into FFTs of size `2 * half_size`
+    let mut half_size = 1;
+    while half_size < n {
+        // The twiddle factors for this layer are the powers of a root of order `2 * half_size`
+        let twiddle_stride = n / (2 * half_size);
+
+        let butterfly = |k: usize, even: &mut T, odd: &mut T| {
+            // The first twiddle factor is always one, so we skip the multiplication
+            let tmp = if k == 0 { *odd } else { *odd * twiddle_factors[k * twiddle_stride] };
+            *odd = *even - tmp;
+            *even = *even + tmp;
+        };
 
-    // Compute a root with half the order
-    let gen_squared = nth_root_of_unity.square();
+        #[cfg(feature = "parallel")]
+        values.par_chunks_exact_mut(2 * half_size).for_each(|chunk| {
+            let (even, odd) = chunk.split_at_mut(half_size);
+            even.par_iter_mut()
+                .zip(odd.par_iter_mut())
+                .enumerate()
+                .for_each(|(k, (even, odd))| butterfly(k, even, odd));
+        });
+
+        #[cfg(not(feature = "parallel"))]
+        for chunk in values.chunks_exact_mut(2 * half_size) {
+            let (even, odd) = chunk.split_at_mut(half_size);
+            for (k, (even, odd)) in even.iter_mut().zip(odd.iter_mut()).enumerate() {
+                butterfly(k, even, odd);
+            }
+        }
 
-    let fft_even = fft_g1(gen_squared, &even);
-    let fft_odd = fft_g1(gen_squared, &odd);
+        half_size *= 2;
+    }
+}
 
-    let mut input_point = blstrs::Scalar::one();
-    let mut evaluations = vec![blstrs::G1Projective::identity(); n];
+#[test]
+fn invalid_domains_are_rejected() {
+    assert!(matches!(
+        Domain::try_new(1 << 33),
+        Err(KzgError::DomainTooLarge { log_size: 33, two_adicity: 32 })
+    ));
+    assert!(matches!(
+        Domain::try_new(usize::MAX / 2 + 2),
+        Err(KzgError::DomainTooLarge { log_size: usize::BITS, two_adicity: 32 })
+    ));
+    assert_eq!(Domain::try_compute_generator_for_size(3), Err(KzgError::NotPowerOfTwo(3)));
+
+    let domain = Domain::new(4);
+    let points = vec![blstrs::G1Affine::generator(); 3];
+    assert_eq!(domain.try_ifft_g1(points), Err(KzgError::LengthMismatch { expected: 4, actual: 3 }));
+}
 
-    for k in 0..n / 2 {
-        let tmp = fft_odd[k] * input_point;
-        evaluations[k] = blstrs::G1Projective::from(fft_even[k]) + tmp;
-        evaluations[k + n / 2] = fft_even[k] - tmp;
+#[test]
+fn fft_scalars_roundtrip() {
+    let size = 16;
+    let coeffs: Vec<_> = (0..size as u64).map(|i| blstrs::Scalar::from(i * i + 1)).collect();
+
+    for domain in [Domain::new(size), Domain::new_bit_reversed(size)] {
+        let evaluations = domain.fft_scalars(coeffs.clone());
+
+        // Compare against evaluating the polynomial directly at each root
+        for (root, evaluation) in domain.roots().iter().zip(&evaluations) {
+            let expected = coeffs
+                .iter()
+                .rev()
+                .fold(blstrs::Scalar::zero(), |acc, coeff| acc * root + coeff);
+            assert_eq!(*evaluation, expected);
+        }
 
-        input_point = input_point * nth_root_of_unity;
+        assert_eq!(domain.ifft_scalars(evaluations), coeffs);
     }
 
-    evaluations
+    // Missing coefficients are treated as zero
+    let domain = Domain::new(size);
+    let evaluations = domain.fft_scalars(vec![blstrs::Scalar::from(5u64)]);
+    assert!(evaluations.iter().all(|evaluation| *evaluation == blstrs::Scalar::from(5u64)));
 }
 
-fn take_even_odd<T: Clone>(list: &[T]) -> (Vec<T>, Vec<T>) {
-    let mut even = Vec::with_capacity(list.len() / 2);
-    let mut odd = Vec::with_capacity(list.len() / 2);
+#[test]
+fn fft_g1_matches_fft_scalars() {
+    let size = 8;
+    let coeffs: Vec<_> = (0..size as u64 - 1).map(|i| blstrs::Scalar::from(3 * i + 2)).collect();
+    let points: Vec<_> = coeffs.iter().map(|coeff| (blstrs::G1Affine::generator() * coeff).to_affine()).collect();
+
+    for domain in [Domain::new(size), Domain::new_bit_reversed(size)] {
+        let expected: Vec<_> = domain
+            .fft_scalars(coeffs.clone())
+            .iter()
+            .map(|evaluation| (blstrs::G1Affine::generator() * evaluation).to_affine())
+            .collect();
+        assert_eq!(domain.fft_g1(points.clone()), expected);
+    }
+}
 
-    for (index, value) in list.iter().enumerate() {
-        if index % 2 == 0 {
-            even.push(value.clone())
-        } else {
-            odd.push(value.clone())
+#[test]
+fn coset_fft_roundtrip() {
+    let size = 8;
+    let shift = blstrs::Scalar::from(7u64);
+    let coeffs: Vec<_> = (0..size as u64).map(|i| blstrs::Scalar::from(i * i + 3)).collect();
+    let points: Vec<_> = coeffs.iter().map(|coeff| (blstrs::G1Affine::generator() * coeff).to_affine()).collect();
+
+    for domain in [Domain::new(size), Domain::new_bit_reversed(size)] {
+        let evaluations = domain.coset_fft_scalars(coeffs.clone(), shift);
+
+        // Compare against evaluating the polynomial directly at each point of the coset
+        for (root, evaluation) in domain.roots().iter().zip(&evaluations) {
+            let point = shift * root;
+            let expected = coeffs.iter().rev().fold(blstrs::Scalar::zero(), |acc, coeff| acc * point + coeff);
+            assert_eq!(*evaluation, expected);
         }
-    }
+        assert_eq!(domain.coset_ifft_scalars(evaluations.clone(), shift), coeffs);
 
-    (even, odd)
+        let point_evaluations = domain.coset_fft_g1(points.clone(), shift);
+        let expected: Vec<_> =
+            evaluations.iter().map(|evaluation| (blstrs::G1Affine::generator() * evaluation).to_affine()).collect();
+        assert_eq!(point_evaluations, expected);
+        assert_eq!(domain.coset_ifft_g1(point_evaluations, shift), points);
+    }
 }
 
 #[test]
@@ -171,5 +462,5 @@ fn largest_group_has_correct_order() {
     let root = Domain::largest_root_of_unity();
     let order = 2u64.pow(Domain::two_adicity());
 
-    assert_eq!(root.pow_vartime(&[order]), blstrs::Scalar::one())
+    assert_eq!(root.pow_vartime([order]), blstrs::Scalar::one())
 }
@@ -0,0 +1,405 @@
+use crate::{
+    domain::Domain, error::KzgError, params::PublicParameters, polynomial::Polynomial, proof::Proof,
+    utils, G1_POINT_SERIALIZED_SIZE, SCALAR_SERIALIZED_SIZE,
+};
+
+use ff::Field;
+use sha2::{Digest, Sha256};
+
+// The number of field elements in a blob
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+// The number of bytes in a blob
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * SCALAR_SERIALIZED_SIZE;
+// Domain separator for the Fiat-Shamir challenge used in blob proofs
+pub const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8; 16] = b"FSBLOBVERIFY_V1_";
+// Domain separator for the random challenge used in batch verification
+pub const RANDOM_CHALLENGE_KZG_BATCH_DOMAIN: &[u8; 16] = b"RCKZGBATCH___V1_";
+// The first byte of the versioned hash of a KZG commitment
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// A serialized scalar, encoded in big-endian
+pub type Bytes32 = [u8; SCALAR_SERIALIZED_SIZE];
+/// A serialized compressed G1 point
+pub type Bytes48 = [u8; G1_POINT_SERIALIZED_SIZE];
+/// `FIELD_ELEMENTS_PER_BLOB` serialized scalars
+pub type Blob = [u8; BYTES_PER_BLOB];
+
+/// A commitment to the polynomial represented by a blob
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KzgCommitment(blstrs::G1Affine);
+
+impl KzgCommitment {
+    pub fn new(point: blstrs::G1Affine) -> KzgCommitment {
+        KzgCommitment(point)
+    }
+
+    /// Deserializes a compressed commitment, checking that it is on the curve and in the correct subgroup
+    pub fn from_bytes(bytes: &Bytes48) -> Result<KzgCommitment, KzgError> {
+        bytes_to_g1(bytes).map(KzgCommitment)
+    }
+
+    pub fn to_bytes(&self) -> Bytes48 {
+        self.0.to_compressed()
+    }
+
+    pub fn point(&self) -> blstrs::G1Affine {
+        self.0
+    }
+
+    /// Returns the hash which execution-layer transactions use to reference this commitment
+    pub fn versioned_hash(&self) -> VersionedHash {
+        kzg_to_versioned_hash(&self.to_bytes())
+    }
+}
+
+impl From<blstrs::G1Affine> for KzgCommitment {
+    fn from(point: blstrs::G1Affine) -> KzgCommitment {
+        KzgCommitment(point)
+    }
+}
+
+/// `VERSIONED_HASH_VERSION_KZG || sha256(commitment)[1..]`
+///
+/// The version byte is checked when deserializing, so a `VersionedHash` always refers to a KZG commitment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VersionedHash(Bytes32);
+
+impl VersionedHash {
+    pub fn from_bytes(bytes: Bytes32) -> Result<VersionedHash, KzgError> {
+        if bytes[0] != VERSIONED_HASH_VERSION_KZG {
+            return Err(KzgError::UnsupportedVersion(bytes[0]));
+        }
+        Ok(VersionedHash(bytes))
+    }
+
+    pub fn to_bytes(&self) -> Bytes32 {
+        self.0
+    }
+
+    pub fn version(&self) -> u8 {
+        self.0[0]
+    }
+}
+
+/// Computes the versioned hash of a serialized commitment
+///
+/// Note: The commitment is not deserialized, so this does not check that it is a valid point
+pub fn kzg_to_versioned_hash(commitment_bytes: &Bytes48) -> VersionedHash {
+    let mut hash: Bytes32 = Sha256::digest(commitment_bytes).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    VersionedHash(hash)
+}
+
+/// Deserializes a big-endian scalar, rejecting values which are not reduced modulo the BLS modulus
+pub fn bytes_to_bls_field(bytes: &Bytes32) -> Result<blstrs::Scalar, KzgError> {
+    Option::from(blstrs::Scalar::from_bytes_be(bytes)).ok_or(KzgError::NonCanonicalScalar)
+}
+
+/// Deserializes a compressed G1 point, checking that it is on the curve and in the correct subgroup
+///
+/// Note: The point at infinity is a valid commitment and proof
+pub fn bytes_to_g1(bytes: &Bytes48) -> Result<blstrs::G1Affine, KzgError> {
+    Option::from(blstrs::G1Affine::from_compressed(bytes)).ok_or(KzgError::MalformedPoint)
+}
+
+/// Deserializes a blob into a polynomial in lagrange form
+///
+/// The evaluations of a blob are stored in bit-reversed order, so the domain used with the
+/// functions in this module should be created with `Domain::new_bit_reversed`
+pub fn blob_to_polynomial(blob: &Blob) -> Result<Polynomial, KzgError> {
+    let evaluations = blob
+        .chunks_exact(SCALAR_SERIALIZED_SIZE)
+        .map(|chunk| bytes_to_bls_field(chunk.try_into().unwrap()))
+        .collect::<Result<_, _>>()?;
+    Ok(Polynomial::new(evaluations))
+}
+
+/// Commits to the polynomial which is represented by `blob`
+pub fn blob_to_kzg_commitment(public_parameters: &PublicParameters, blob: &Blob) -> Result<Bytes48, KzgError> {
+    let polynomial = blob_to_polynomial(blob)?;
+    Ok(public_parameters.commit_key.commit(&polynomial).to_compressed())
+}
+
+/// Computes a proof that the polynomial represented by `blob` evaluates to `y` at `z`
+///
+/// Returns the proof and `y`
+pub fn compute_kzg_proof(
+    public_parameters: &PublicParameters,
+    domain: &Domain,
+    blob: &Blob,
+    z_bytes: &Bytes32,
+) -> Result<(Bytes48, Bytes32), KzgError> {
+    let polynomial = blob_to_polynomial(blob)?;
+    let input_point = bytes_to_bls_field(z_bytes)?;
+
+    // We do not use `Proof::create` since the commitment to the polynomial is not needed
+    let output_point = polynomial.evaluate(input_point, domain);
+    let quotient = utils::compute(&polynomial, input_point, output_point, domain);
+    let quotient_commitment = public_parameters.commit_key.commit(&quotient);
+
+    Ok((quotient_commitment.to_compressed(), output_point.to_bytes_be()))
+}
+
+/// Verifies that the polynomial committed to by `commitment_bytes` evaluates to `y` at `z`
+pub fn verify_kzg_proof(
+    public_parameters: &PublicParameters,
+    commitment_bytes: &Bytes48,
+    z_bytes: &Bytes32,
+    y_bytes: &Bytes32,
+    proof_bytes: &Bytes48,
+) -> Result<bool, KzgError> {
+    let poly_comm = bytes_to_g1(commitment_bytes)?;
+    let input_point = bytes_to_bls_field(z_bytes)?;
+    let output_point = bytes_to_bls_field(y_bytes)?;
+    let witness_comm = bytes_to_g1(proof_bytes)?;
+
+    Ok(public_parameters.opening_key.verify(input_point, output_point, poly_comm, witness_comm))
+}
+
+/// Hashes `data` with SHA-256 and reduces the digest modulo the BLS modulus
+pub fn hash_to_bls_field(data: &[u8]) -> blstrs::Scalar {
+    let hashed_data: [u8; 32] = Sha256::digest(data).into();
+
+    // The digest is a big-endian integer which may be larger than the modulus,
+    // so we fold it in one 64 bit limb at a time
+    let two_pow_64 = blstrs::Scalar::from(u64::MAX) + blstrs::Scalar::one();
+    hashed_data.chunks_exact(8).fold(blstrs::Scalar::zero(), |acc, limb| {
+        acc * two_pow_64 + blstrs::Scalar::from(u64::from_be_bytes(limb.try_into().unwrap()))
+    })
+}
+
+/// Computes the Fiat-Shamir challenge which is used as the evaluation point for blob proofs
+pub fn compute_challenge(blob: &Blob, commitment_bytes: &Bytes48) -> blstrs::Scalar {
+    // The degree of the polynomial is encoded as a 16 byte big-endian integer
+    let degree_poly = (FIELD_ELEMENTS_PER_BLOB as u128).to_be_bytes();
+
+    let mut data = Vec::with_capacity(
+        FIAT_SHAMIR_PROTOCOL_DOMAIN.len() + degree_poly.len() + BYTES_PER_BLOB + G1_POINT_SERIALIZED_SIZE,
+    );
+    data.extend_from_slice(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+    data.extend_from_slice(&degree_poly);
+    data.extend_from_slice(blob);
+    data.extend_from_slice(commitment_bytes);
+
+    hash_to_bls_field(&data)
+}
+
+/// Computes a proof for `blob` at the Fiat-Shamir challenge derived from the blob and its commitment
+pub fn compute_blob_kzg_proof(
+    public_parameters: &PublicParameters,
+    domain: &Domain,
+    blob: &Blob,
+    commitment_bytes: &Bytes48,
+) -> Result<Bytes48, KzgError> {
+    let poly_comm = bytes_to_g1(commitment_bytes)?;
+    let polynomial = blob_to_polynomial(blob)?;
+    let evaluation_challenge = compute_challenge(blob, commitment_bytes);
+
+    let proof = Proof::create(&public_parameters.commit_key, &polynomial, poly_comm, evaluation_challenge, domain);
+    Ok(proof.quotient_commitment.to_compressed())
+}
+
+/// Verifies a proof created by `compute_blob_kzg_proof`
+pub fn verify_blob_kzg_proof(
+    public_parameters: &PublicParameters,
+    domain: &Domain,
+    blob: &Blob,
+    commitment_bytes: &Bytes48,
+    proof_bytes: &Bytes48,
+) -> Result<bool, KzgError> {
+    let poly_comm = bytes_to_g1(commitment_bytes)?;
+    let polynomial = blob_to_polynomial(blob)?;
+    let witness_comm = bytes_to_g1(proof_bytes)?;
+
+    let evaluation_challenge = compute_challenge(blob, commitment_bytes);
+    let output_point = polynomial.evaluate(evaluation_challenge, domain);
+
+    Ok(public_parameters.opening_key.verify(evaluation_challenge, output_point, poly_comm, witness_comm))
+}
+
+/// Computes the random challenge used to fold a batch of proofs into a single pairing check
+///
+/// The challenge is derived from every commitment, input point, output point and proof in the batch
+pub fn compute_batch_challenge(
+    commitments: &[blstrs::G1Affine],
+    input_points: &[blstrs::Scalar],
+    output_points: &[blstrs::Scalar],
+    proofs: &[blstrs::G1Affine],
+) -> blstrs::Scalar {
+    let mut data = Vec::new();
+    data.extend_from_slice(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN);
+    data.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+    data.extend_from_slice(&(commitments.len() as u64).to_be_bytes());
+
+    for (((commitment, z), y), proof) in commitments.iter().zip(input_points).zip(output_points).zip(proofs) {
+        data.extend_from_slice(&commitment.to_compressed());
+        data.extend_from_slice(&z.to_bytes_be());
+        data.extend_from_slice(&y.to_bytes_be());
+        data.extend_from_slice(&proof.to_compressed());
+    }
+
+    hash_to_bls_field(&data)
+}
+
+/// Verifies a batch of proofs created by `compute_blob_kzg_proof`, using a single pairing check
+pub fn verify_blob_kzg_proof_batch(
+    public_parameters: &PublicParameters,
+    domain: &Domain,
+    blobs: &[Blob],
+    commitments_bytes: &[Bytes48],
+    proofs_bytes: &[Bytes48],
+) -> Result<bool, KzgError> {
+    for other_len in [commitments_bytes.len(), proofs_bytes.len()] {
+        if other_len != blobs.len() {
+            return Err(KzgError::LengthMismatch { expected: blobs.len(), actual: other_len });
+        }
+    }
+
+    let mut poly_comms = Vec::with_capacity(blobs.len());
+    let mut input_points = Vec::with_capacity(blobs.len());
+    let mut output_points = Vec::with_capacity(blobs.len());
+    let mut witness_comms = Vec::with_capacity(blobs.len());
+
+    for ((blob, commitment_bytes), proof_bytes) in blobs.iter().zip(commitments_bytes).zip(proofs_bytes) {
+        let poly_comm = bytes_to_g1(commitment_bytes)?;
+        let polynomial = blob_to_polynomial(blob)?;
+        let evaluation_challenge = compute_challenge(blob, commitment_bytes);
+        let output_point = polynomial.evaluate(evaluation_challenge, domain);
+        let witness_comm = bytes_to_g1(proof_bytes)?;
+
+        poly_comms.push(poly_comm);
+        input_points.push(evaluation_challenge);
+        output_points.push(output_point);
+        witness_comms.push(witness_comm);
+    }
+
+    let challenge = compute_batch_challenge(&poly_comms, &input_points, &output_points, &witness_comms);
+
+    let opening_key = &public_parameters.opening_key;
+    opening_key.verify_batch(&input_points, &output_points, &poly_comms, &witness_comms, challenge)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use group::prime::PrimeCurveAffine;
+
+    use super::*;
+
+    fn random_blob() -> Box<Blob> {
+        let mut blob = Box::new([0u8; BYTES_PER_BLOB]);
+        for chunk in blob.chunks_exact_mut(SCALAR_SERIALIZED_SIZE) {
+            chunk.copy_from_slice(&blstrs::Scalar::random(&mut rand::thread_rng()).to_bytes_be());
+        }
+        blob
+    }
+
+    #[test]
+    fn valid_kzg_proof_bytes() {
+        let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+        let public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+
+        let blob = random_blob();
+        let commitment = blob_to_kzg_commitment(&public_parameters, &blob).unwrap();
+
+        // Open at a point outside of the domain and at a point inside of the domain
+        let in_domain = domain.roots()[1].to_bytes_be();
+        let outside_domain = blstrs::Scalar::from(123456u64).to_bytes_be();
+
+        for z in [in_domain, outside_domain] {
+            let (proof, y) = compute_kzg_proof(&public_parameters, &domain, &blob, &z).unwrap();
+            assert!(verify_kzg_proof(&public_parameters, &commitment, &z, &y, &proof).unwrap());
+
+            let wrong_y = (bytes_to_bls_field(&y).unwrap() + blstrs::Scalar::one()).to_bytes_be();
+            assert!(!verify_kzg_proof(&public_parameters, &commitment, &z, &wrong_y, &proof).unwrap());
+        }
+    }
+
+    #[test]
+    fn valid_blob_kzg_proof() {
+        let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+        let public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+
+        let blob = random_blob();
+        let commitment = blob_to_kzg_commitment(&public_parameters, &blob).unwrap();
+        let proof = compute_blob_kzg_proof(&public_parameters, &domain, &blob, &commitment).unwrap();
+        assert!(verify_blob_kzg_proof(&public_parameters, &domain, &blob, &commitment, &proof).unwrap());
+
+        // The challenge depends on the blob, so the proof is not valid for any other blob
+        let other_blob = random_blob();
+        assert!(!verify_blob_kzg_proof(&public_parameters, &domain, &other_blob, &commitment, &proof).unwrap());
+    }
+
+    #[test]
+    fn valid_blob_kzg_proof_batch() {
+        let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+        let public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+
+        let blobs: Vec<Blob> = (0..3).map(|_| *random_blob()).collect();
+        let commitments: Vec<_> = blobs
+            .iter()
+            .map(|blob| blob_to_kzg_commitment(&public_parameters, blob).unwrap())
+            .collect();
+        let mut proofs: Vec<_> = blobs
+            .iter()
+            .zip(&commitments)
+            .map(|(blob, commitment)| {
+                compute_blob_kzg_proof(&public_parameters, &domain, blob, commitment).unwrap()
+            })
+            .collect();
+
+        assert!(verify_blob_kzg_proof_batch(&public_parameters, &domain, &blobs, &commitments, &proofs).unwrap());
+        assert!(verify_blob_kzg_proof_batch(&public_parameters, &domain, &[], &[], &[]).unwrap());
+        assert_eq!(
+            verify_blob_kzg_proof_batch(&public_parameters, &domain, &blobs, &commitments, &proofs[1..]),
+            Err(KzgError::LengthMismatch { expected: 3, actual: 2 })
+        );
+
+        proofs.swap(0, 1);
+        assert!(!verify_blob_kzg_proof_batch(&public_parameters, &domain, &blobs, &commitments, &proofs).unwrap());
+    }
+
+    #[test]
+    fn hash_to_bls_field_reduces_digest() {
+        // Reducing the digest byte by byte should agree with reducing it limb by limb
+        let digest: [u8; 32] = Sha256::digest(b"abc").into();
+        let expected = digest.iter().fold(blstrs::Scalar::zero(), |acc, byte| {
+            acc * blstrs::Scalar::from(256u64) + blstrs::Scalar::from(*byte as u64)
+        });
+        assert_eq!(hash_to_bls_field(b"abc"), expected);
+    }
+
+    #[test]
+    fn invalid_inputs_are_rejected() {
+        // The BLS modulus is not a canonical scalar
+        let modulus = (-blstrs::Scalar::one()).to_bytes_be();
+        let mut non_canonical = modulus;
+        non_canonical[SCALAR_SERIALIZED_SIZE - 1] += 1;
+        assert_eq!(bytes_to_bls_field(&non_canonical), Err(KzgError::NonCanonicalScalar));
+
+        let mut blob = random_blob();
+        blob[..SCALAR_SERIALIZED_SIZE].copy_from_slice(&non_canonical);
+        assert_eq!(blob_to_polynomial(&blob), Err(KzgError::NonCanonicalScalar));
+
+        // The compression flag is not set, so this cannot be a compressed point
+        assert_eq!(bytes_to_g1(&[0u8; G1_POINT_SERIALIZED_SIZE]), Err(KzgError::MalformedPoint));
+    }
+
+    #[test]
+    fn versioned_hash() {
+        let commitment = KzgCommitment::new(blstrs::G1Affine::generator());
+        let commitment_bytes = commitment.to_bytes();
+        assert_eq!(KzgCommitment::from_bytes(&commitment_bytes), Ok(commitment));
+
+        let digest: Bytes32 = Sha256::digest(commitment_bytes).into();
+        let versioned_hash = commitment.versioned_hash();
+        assert_eq!(versioned_hash.version(), VERSIONED_HASH_VERSION_KZG);
+        assert_eq!(versioned_hash.to_bytes()[1..], digest[1..]);
+        assert_eq!(versioned_hash, kzg_to_versioned_hash(&commitment_bytes));
+
+        assert_eq!(VersionedHash::from_bytes(versioned_hash.to_bytes()), Ok(versioned_hash));
+        let mut wrong_version = versioned_hash.to_bytes();
+        wrong_version[0] = 0x02;
+        assert_eq!(VersionedHash::from_bytes(wrong_version), Err(KzgError::UnsupportedVersion(0x02)));
+    }
+}
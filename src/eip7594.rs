@@ -0,0 +1,615 @@
+use std::collections::HashMap;
+
+use blstrs::{Bls12, G2Prepared};
+use ff::Field;
+use pairing_lib::{group::Group, MillerLoopResult, MultiMillerLoop};
+
+use crate::{
+    commit_key::g1_lincomb,
+    das::{extend_polynomial, recover_polynomial, EXTENSION_FACTOR},
+    domain::Domain,
+    eip4844::{
+        blob_to_polynomial, bytes_to_bls_field, bytes_to_g1, hash_to_bls_field, Blob, Bytes48,
+        FIELD_ELEMENTS_PER_BLOB,
+    },
+    error::KzgError,
+    params::PublicParameters,
+    polynomial::Polynomial,
+    polynomial_coeff::PolynomialCoeff,
+    utils::compute_powers,
+    SCALAR_SERIALIZED_SIZE,
+};
+
+// The number of field elements in an extended blob
+pub const FIELD_ELEMENTS_PER_EXT_BLOB: usize = EXTENSION_FACTOR * FIELD_ELEMENTS_PER_BLOB;
+// The number of field elements in a cell
+pub const FIELD_ELEMENTS_PER_CELL: usize = 64;
+// The number of cells that an extended blob is split into
+pub const CELLS_PER_EXT_BLOB: usize = FIELD_ELEMENTS_PER_EXT_BLOB / FIELD_ELEMENTS_PER_CELL;
+// The number of bytes in a cell
+pub const BYTES_PER_CELL: usize = FIELD_ELEMENTS_PER_CELL * SCALAR_SERIALIZED_SIZE;
+// Domain separator for the random challenge used in cell batch verification
+pub const RANDOM_CHALLENGE_KZG_CELL_BATCH_DOMAIN: &[u8; 16] = b"RCKZGCBATCH__V1_";
+
+/// `FIELD_ELEMENTS_PER_CELL` serialized scalars
+pub type Cell = [u8; BYTES_PER_CELL];
+/// The position of a cell in the extended blob, which is less than `CELLS_PER_EXT_BLOB`
+pub type CellIndex = u64;
+
+/// Returns the points that the evaluations in cell `cell_index` are at
+///
+/// Cells are consecutive chunks of the extended blob, which is stored in bit-reversed order,
+/// so the points of each cell are a coset `h * {\omega : \omega^FIELD_ELEMENTS_PER_CELL = 1}`, where `h`
+/// is the first point.
+///
+/// `extended_domain` must be a bit-reversed domain of size `FIELD_ELEMENTS_PER_EXT_BLOB`
+pub fn coset_for_cell(extended_domain: &Domain, cell_index: CellIndex) -> Result<&[blstrs::Scalar], KzgError> {
+    let cell_index = checked_cell_index(cell_index)?;
+    let start = cell_index * FIELD_ELEMENTS_PER_CELL;
+    Ok(&extended_domain.roots()[start..start + FIELD_ELEMENTS_PER_CELL])
+}
+
+/// Deserializes a cell into the evaluations at the points of its coset
+pub fn cell_to_coset_evals(cell: &Cell) -> Result<Vec<blstrs::Scalar>, KzgError> {
+    cell.chunks_exact(SCALAR_SERIALIZED_SIZE)
+        .map(|chunk| bytes_to_bls_field(chunk.try_into().unwrap()))
+        .collect()
+}
+
+/// Serializes the evaluations at the points of a coset into a cell
+///
+/// Panics, if there are not `FIELD_ELEMENTS_PER_CELL` evaluations
+pub fn coset_evals_to_cell(coset_evals: &[blstrs::Scalar]) -> Cell {
+    assert_eq!(coset_evals.len(), FIELD_ELEMENTS_PER_CELL, "a cell has {FIELD_ELEMENTS_PER_CELL} field elements");
+
+    let mut cell = [0u8; BYTES_PER_CELL];
+    for (chunk, evaluation) in cell.chunks_exact_mut(SCALAR_SERIALIZED_SIZE).zip(coset_evals) {
+        chunk.copy_from_slice(&evaluation.to_bytes_be());
+    }
+    cell
+}
+
+/// Extends `blob` and splits the extended blob into `CELLS_PER_EXT_BLOB` cells
+///
+/// `domain` and `extended_domain` must be bit-reversed domains of size `FIELD_ELEMENTS_PER_BLOB`
+/// and `FIELD_ELEMENTS_PER_EXT_BLOB`. The first half of the cells are the blob itself.
+pub fn compute_cells(domain: &Domain, extended_domain: &Domain, blob: &Blob) -> Result<Vec<Cell>, KzgError> {
+    let polynomial = blob_to_polynomial(blob)?;
+    let extended = extend_polynomial(&polynomial, domain, extended_domain);
+    Ok(extended.evaluations.chunks_exact(FIELD_ELEMENTS_PER_CELL).map(coset_evals_to_cell).collect())
+}
+
+/// Computes the cells of `blob` and a proof for each cell that its evaluations are on the committed polynomial
+///
+/// The proof for a cell with coset `H` is a commitment to the quotient `(p(X) - I(X)) / Z_H(X)`,
+/// where `I(X)` interpolates `p(X)` over `H` and `Z_H(X)` vanishes on `H`.
+/// Since `H` is a coset of a subgroup, `Z_H(X) = X^FIELD_ELEMENTS_PER_CELL - h^FIELD_ELEMENTS_PER_CELL`.
+///
+/// If `PublicParameters::precompute_cell_proofs` has been called, the proofs are all computed at once with FK20.
+pub fn compute_cells_and_kzg_proofs(
+    public_parameters: &PublicParameters,
+    domain: &Domain,
+    extended_domain: &Domain,
+    blob: &Blob,
+) -> Result<(Vec<Cell>, Vec<Bytes48>), KzgError> {
+    let polynomial = blob_to_polynomial(blob)?;
+    let poly_coeff = PolynomialCoeff::from_polynomial(&polynomial, domain);
+    let extended = extend_polynomial(&polynomial, domain, extended_domain);
+
+    let cells = extended.evaluations.chunks_exact(FIELD_ELEMENTS_PER_CELL).map(coset_evals_to_cell).collect();
+    let proofs = compute_cell_proofs(public_parameters, domain, extended_domain, &poly_coeff)?;
+
+    Ok((cells, proofs))
+}
+
+/// Recovers all of the cells of an extended blob and their proofs, from at least half of the cells
+///
+/// The cells are decoded as evaluations of the extended polynomial with `das::recover_polynomial`,
+/// and the proofs are then computed from the recovered polynomial like in `compute_cells_and_kzg_proofs`.
+///
+/// Returns an error, if there are fewer than `CELLS_PER_EXT_BLOB / 2` cells, a cell index is out of range
+/// or repeated, or if the cells are not all from one extended blob.
+pub fn recover_cells_and_kzg_proofs(
+    public_parameters: &PublicParameters,
+    domain: &Domain,
+    extended_domain: &Domain,
+    cell_indices: &[CellIndex],
+    cells: &[Cell],
+) -> Result<(Vec<Cell>, Vec<Bytes48>), KzgError> {
+    if cell_indices.len() != cells.len() {
+        return Err(KzgError::LengthMismatch { expected: cell_indices.len(), actual: cells.len() });
+    }
+    let min_cells = CELLS_PER_EXT_BLOB / EXTENSION_FACTOR;
+    if cells.len() < min_cells {
+        return Err(KzgError::TooFewPoints { minimum: min_cells, actual: cells.len() });
+    }
+
+    let mut is_known = [false; CELLS_PER_EXT_BLOB];
+    let mut indices = Vec::with_capacity(cells.len() * FIELD_ELEMENTS_PER_CELL);
+    let mut evaluations = Vec::with_capacity(cells.len() * FIELD_ELEMENTS_PER_CELL);
+    for (cell_index, cell) in cell_indices.iter().zip(cells) {
+        let cell_position = checked_cell_index(*cell_index)?;
+        if is_known[cell_position] {
+            return Err(KzgError::DuplicateIndex(cell_position));
+        }
+        is_known[cell_position] = true;
+
+        let start = cell_position * FIELD_ELEMENTS_PER_CELL;
+        indices.extend(start..start + FIELD_ELEMENTS_PER_CELL);
+        evaluations.extend(cell_to_coset_evals(cell)?);
+    }
+
+    let extended = recover_polynomial(&indices, &evaluations, domain, extended_domain)?;
+
+    // The first half of the extended evaluations are the evaluations of the blob
+    let polynomial = Polynomial::new(extended.evaluations[..FIELD_ELEMENTS_PER_BLOB].to_vec());
+    let poly_coeff = PolynomialCoeff::from_polynomial(&polynomial, domain);
+
+    let cells = extended.evaluations.chunks_exact(FIELD_ELEMENTS_PER_CELL).map(coset_evals_to_cell).collect();
+    let proofs = compute_cell_proofs(public_parameters, domain, extended_domain, &poly_coeff)?;
+
+    Ok((cells, proofs))
+}
+
+/// Computes the challenge used to combine the cell proofs in `verify_cell_kzg_proof_batch`
+///
+/// `commitments_bytes` are the deduplicated commitments, and `commitment_indices[k]` is the position of
+/// the commitment for cell `k` in them. The cells must be canonical, so they hash the same as their evaluations.
+pub fn compute_verify_cell_kzg_proof_batch_challenge(
+    commitments_bytes: &[Bytes48],
+    commitment_indices: &[u64],
+    cell_indices: &[CellIndex],
+    cells: &[Cell],
+    proofs_bytes: &[Bytes48],
+) -> blstrs::Scalar {
+    let mut hash_input = Vec::with_capacity(
+        RANDOM_CHALLENGE_KZG_CELL_BATCH_DOMAIN.len()
+            + 4 * 8
+            + commitments_bytes.len() * 48
+            + cells.len() * (2 * 8 + BYTES_PER_CELL + 48),
+    );
+    hash_input.extend_from_slice(RANDOM_CHALLENGE_KZG_CELL_BATCH_DOMAIN);
+    hash_input.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+    hash_input.extend_from_slice(&(FIELD_ELEMENTS_PER_CELL as u64).to_be_bytes());
+    hash_input.extend_from_slice(&(commitments_bytes.len() as u64).to_be_bytes());
+    hash_input.extend_from_slice(&(cells.len() as u64).to_be_bytes());
+
+    for commitment_bytes in commitments_bytes {
+        hash_input.extend_from_slice(commitment_bytes);
+    }
+    for (((commitment_index, cell_index), cell), proof_bytes) in
+        commitment_indices.iter().zip(cell_indices).zip(cells).zip(proofs_bytes)
+    {
+        hash_input.extend_from_slice(&commitment_index.to_be_bytes());
+        hash_input.extend_from_slice(&cell_index.to_be_bytes());
+        hash_input.extend_from_slice(cell);
+        hash_input.extend_from_slice(proof_bytes);
+    }
+
+    hash_to_bls_field(&hash_input)
+}
+
+/// Verifies that every `cells[k]` is the cell `cell_indices[k]` of the polynomial committed to in
+/// `commitments_bytes[k]`, using the proofs in `proofs_bytes`
+///
+/// For a cell with coset `h_k * H`, the proof `\pi_k` satisfies `C_k - [I_k(\tau)] = [\tau^n - h_k^n] \pi_k`,
+/// where `n = FIELD_ELEMENTS_PER_CELL`. These equations are combined with powers of a challenge `r` into:
+///
+/// `e(\sum r^k C_k - [\sum r^k I_k(\tau)] + \sum r^k h_k^n \pi_k, G2) = e(\sum r^k \pi_k, \tau^n G2)`
+///
+/// so that only two pairings are needed. Repeated commitments are only multiplied once, and the
+/// interpolation polynomials of cells with the same index are combined before interpolating.
+pub fn verify_cell_kzg_proof_batch(
+    public_parameters: &PublicParameters,
+    domain: &Domain,
+    extended_domain: &Domain,
+    commitments_bytes: &[Bytes48],
+    cell_indices: &[CellIndex],
+    cells: &[Cell],
+    proofs_bytes: &[Bytes48],
+) -> Result<bool, KzgError> {
+    let num_cells = cells.len();
+    for other_len in [commitments_bytes.len(), cell_indices.len(), proofs_bytes.len()] {
+        if other_len != num_cells {
+            return Err(KzgError::LengthMismatch { expected: num_cells, actual: other_len });
+        }
+    }
+    let cell_positions = cell_indices
+        .iter()
+        .map(|cell_index| checked_cell_index(*cell_index))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Deduplicate the commitments, keeping the order in which they first appear
+    let mut unique_commitments_bytes = Vec::new();
+    let mut unique_positions = HashMap::new();
+    let commitment_indices: Vec<u64> = commitments_bytes
+        .iter()
+        .map(|commitment_bytes| {
+            *unique_positions.entry(*commitment_bytes).or_insert_with(|| {
+                unique_commitments_bytes.push(*commitment_bytes);
+                unique_commitments_bytes.len() as u64 - 1
+            })
+        })
+        .collect();
+
+    let commitments = unique_commitments_bytes.iter().map(bytes_to_g1).collect::<Result<Vec<_>, _>>()?;
+    let cosets_evals = cells.iter().map(cell_to_coset_evals).collect::<Result<Vec<_>, _>>()?;
+    let proofs = proofs_bytes.iter().map(bytes_to_g1).collect::<Result<Vec<_>, _>>()?;
+
+    if num_cells == 0 {
+        return Ok(true);
+    }
+
+    let opening_key = &public_parameters.opening_key;
+    let tau_pow_n_g2 = *opening_key.g2_monomial.get(FIELD_ELEMENTS_PER_CELL).ok_or(KzgError::TooFewPoints {
+        minimum: FIELD_ELEMENTS_PER_CELL + 1,
+        actual: opening_key.g2_monomial.len(),
+    })?;
+
+    let challenge = compute_verify_cell_kzg_proof_batch_challenge(
+        &unique_commitments_bytes,
+        &commitment_indices,
+        cell_indices,
+        cells,
+        proofs_bytes,
+    );
+    let r_powers = compute_powers(challenge, num_cells);
+
+    // sum r^k * \pi_k
+    let proof_lincomb = g1_lincomb(&proofs, &r_powers);
+
+    // sum r^k * C_k, where the powers of r for the same commitment are added together
+    let mut commitment_weights = vec![blstrs::Scalar::zero(); commitments.len()];
+    for (commitment_index, r_power) in commitment_indices.iter().zip(&r_powers) {
+        commitment_weights[*commitment_index as usize] += r_power;
+    }
+    let comm_lincomb = g1_lincomb(&commitments, &commitment_weights);
+
+    // [sum r^k * I_k(\tau)]
+    let interpolation_comm = commit_to_aggregated_interpolation(
+        public_parameters,
+        domain,
+        extended_domain,
+        &cell_positions,
+        &cosets_evals,
+        &r_powers,
+    );
+
+    // sum r^k * h_k^n * \pi_k
+    let weighted_r_powers: Vec<_> = cell_positions
+        .iter()
+        .zip(&r_powers)
+        .map(|(cell_position, r_power)| {
+            let coset_shift = extended_domain.roots()[cell_position * FIELD_ELEMENTS_PER_CELL];
+            r_power * coset_shift.pow_vartime([FIELD_ELEMENTS_PER_CELL as u64])
+        })
+        .collect();
+    let weighted_proof_lincomb = g1_lincomb(&proofs, &weighted_r_powers);
+
+    // e(sum r^k * (C_k - I_k(\tau) + h_k^n * \pi_k), G2) * e(-sum r^k * \pi_k, \tau^n * G2) == 1
+    let inner_a: blstrs::G1Affine = (blstrs::G1Projective::from(comm_lincomb) - interpolation_comm
+        + weighted_proof_lincomb)
+        .into();
+    let inner_b: blstrs::G1Affine = -proof_lincomb;
+
+    let prepared_tau_pow_n_g2 = G2Prepared::from(tau_pow_n_g2);
+    let terms = [(&inner_a, &opening_key.prepared_g2), (&inner_b, &prepared_tau_pow_n_g2)];
+    let pairing = Bls12::multi_miller_loop(&terms).final_exponentiation();
+
+    Ok(pairing.is_identity().into())
+}
+
+// Commits to `sum r^k * I_k(X)`, where `I_k(X)` interpolates `cosets_evals[k]` over the coset of cell `k`
+//
+// The interpolation is linear, so the evaluations for the same cell index are combined first
+fn commit_to_aggregated_interpolation(
+    public_parameters: &PublicParameters,
+    domain: &Domain,
+    extended_domain: &Domain,
+    cell_positions: &[usize],
+    cosets_evals: &[Vec<blstrs::Scalar>],
+    r_powers: &[blstrs::Scalar],
+) -> blstrs::G1Affine {
+    let mut aggregated_evals: Vec<Option<Vec<blstrs::Scalar>>> = vec![None; CELLS_PER_EXT_BLOB];
+    for ((cell_position, coset_evals), r_power) in cell_positions.iter().zip(cosets_evals).zip(r_powers) {
+        let aggregated = aggregated_evals[*cell_position]
+            .get_or_insert_with(|| vec![blstrs::Scalar::zero(); FIELD_ELEMENTS_PER_CELL]);
+        for (aggregated_eval, coset_eval) in aggregated.iter_mut().zip(coset_evals) {
+            *aggregated_eval += r_power * coset_eval;
+        }
+    }
+
+    // The coset of a cell is `h * roots` in bit-reversed order, so interpolating over `roots`
+    // gives the coefficients of `I(h * X)`, which are `h^i` times the coefficients of `I(X)`
+    let cell_domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_CELL);
+    let mut interpolation = PolynomialCoeff::zero();
+    for (cell_position, evals) in aggregated_evals.into_iter().enumerate() {
+        let Some(evals) = evals else { continue };
+
+        let coset_shift = extended_domain.roots()[cell_position * FIELD_ELEMENTS_PER_CELL];
+        let coeffs = cell_domain.coset_ifft_scalars(evals, coset_shift);
+        interpolation = &interpolation + &PolynomialCoeff::new(coeffs);
+    }
+
+    // The interpolation polynomial has degree less than the blob size, so we can commit to it in lagrange form
+    let interpolation = Polynomial::new(domain.fft_scalars(interpolation.coeffs));
+    public_parameters.commit_key.commit(&interpolation)
+}
+
+// Computes the proofs for all of the cells of the extended polynomial, with FK20 if it has been precomputed
+fn compute_cell_proofs(
+    public_parameters: &PublicParameters,
+    domain: &Domain,
+    extended_domain: &Domain,
+    poly_coeff: &PolynomialCoeff,
+) -> Result<Vec<Bytes48>, KzgError> {
+    match &public_parameters.cell_proofs {
+        Some(fk20) => {
+            // `cell_proofs` can be set directly, so it may not have been checked by `precompute_cell_proofs`
+            if fk20.num_cosets() != CELLS_PER_EXT_BLOB {
+                return Err(KzgError::LengthMismatch { expected: CELLS_PER_EXT_BLOB, actual: fk20.num_cosets() });
+            }
+            Ok(fk20.try_compute_proofs(poly_coeff)?.iter().map(blstrs::G1Affine::to_compressed).collect())
+        }
+        None => (0..CELLS_PER_EXT_BLOB as CellIndex)
+            .map(|cell_index| {
+                let coset_shift = coset_for_cell(extended_domain, cell_index)?[0];
+                let quotient = cell_quotient(poly_coeff, coset_shift);
+
+                // The quotient has degree less than the blob size, so we can commit to it in lagrange form
+                let quotient = Polynomial::new(domain.fft_scalars(quotient.coeffs));
+                Ok(public_parameters.commit_key.commit(&quotient).to_compressed())
+            })
+            .collect(),
+    }
+}
+
+// Computes the quotient of `poly_coeff` by the polynomial that vanishes on the cell coset `coset_shift * H`
+fn cell_quotient(poly_coeff: &PolynomialCoeff, coset_shift: blstrs::Scalar) -> PolynomialCoeff {
+    let vanishing_constant = coset_shift.pow_vartime([FIELD_ELEMENTS_PER_CELL as u64]);
+    let (quotient, _) = poly_coeff.divide_by_binomial(FIELD_ELEMENTS_PER_CELL, vanishing_constant);
+    quotient
+}
+
+fn checked_cell_index(cell_index: CellIndex) -> Result<usize, KzgError> {
+    if cell_index >= CELLS_PER_EXT_BLOB as CellIndex {
+        return Err(KzgError::IndexOutOfRange { index: cell_index as usize, size: CELLS_PER_EXT_BLOB });
+    }
+    Ok(cell_index as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::seq::SliceRandom;
+
+    use super::*;
+    use crate::{
+        commit_key::CommitKey,
+        eip4844::{blob_to_kzg_commitment, BYTES_PER_BLOB},
+    };
+
+    fn random_blob() -> Box<Blob> {
+        let mut blob = Box::new([0u8; BYTES_PER_BLOB]);
+        for chunk in blob.chunks_exact_mut(SCALAR_SERIALIZED_SIZE) {
+            chunk.copy_from_slice(&blstrs::Scalar::random(&mut rand::thread_rng()).to_bytes_be());
+        }
+        blob
+    }
+
+    fn monomial_commit_key(tau: blstrs::Scalar) -> CommitKey {
+        let generator = blstrs::G1Projective::generator();
+        CommitKey::new(compute_powers(tau, FIELD_ELEMENTS_PER_BLOB).iter().map(|tau_pow| (generator * tau_pow).into()).collect())
+    }
+
+    #[test]
+    fn cell_cosets() {
+        let extended_domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_EXT_BLOB);
+
+        for cell_index in [0, 1, 77, CELLS_PER_EXT_BLOB as CellIndex - 1] {
+            let coset = coset_for_cell(&extended_domain, cell_index).unwrap();
+            let vanishing_constant = coset[0].pow_vartime([FIELD_ELEMENTS_PER_CELL as u64]);
+            let is_on_coset = |point: &blstrs::Scalar| point.pow_vartime([FIELD_ELEMENTS_PER_CELL as u64]) == vanishing_constant;
+            assert!(coset.iter().all(is_on_coset));
+        }
+
+        assert_eq!(
+            coset_for_cell(&extended_domain, CELLS_PER_EXT_BLOB as CellIndex),
+            Err(KzgError::IndexOutOfRange { index: CELLS_PER_EXT_BLOB, size: CELLS_PER_EXT_BLOB })
+        );
+    }
+
+    #[test]
+    fn verify_cell_proofs() {
+        let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+        let extended_domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_EXT_BLOB);
+        let public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+        let verify = |commitments: &[Bytes48], cell_indices: &[CellIndex], cells: &[Cell], proofs: &[Bytes48]| {
+            verify_cell_kzg_proof_batch(
+                &public_parameters,
+                &domain,
+                &extended_domain,
+                commitments,
+                cell_indices,
+                cells,
+                proofs,
+            )
+        };
+
+        let blobs = [random_blob(), random_blob()];
+        let mut commitments = Vec::new();
+        let mut cell_indices = Vec::new();
+        let mut cells = Vec::new();
+        let mut proofs = Vec::new();
+        for blob in &blobs {
+            let commitment = blob_to_kzg_commitment(&public_parameters, blob).unwrap();
+            let (blob_cells, blob_proofs) =
+                compute_cells_and_kzg_proofs(&public_parameters, &domain, &extended_domain, blob).unwrap();
+
+            // Both blobs share some of the cell indices, and the first commitment is repeated
+            for cell_index in [0, 3, 100, 127] {
+                commitments.push(commitment);
+                cell_indices.push(cell_index as CellIndex);
+                cells.push(blob_cells[cell_index]);
+                proofs.push(blob_proofs[cell_index]);
+            }
+        }
+
+        assert_eq!(verify(&commitments, &cell_indices, &cells, &proofs), Ok(true));
+        assert_eq!(verify(&commitments[..1], &cell_indices[..1], &cells[..1], &proofs[..1]), Ok(true));
+        assert_eq!(verify(&[], &[], &[], &[]), Ok(true));
+
+        // A proof for the wrong cell, a wrong cell and a wrong commitment are all detected
+        let mut wrong_proofs = proofs.clone();
+        wrong_proofs.swap(0, 1);
+        assert_eq!(verify(&commitments, &cell_indices, &cells, &wrong_proofs), Ok(false));
+
+        let mut wrong_cells = cells.clone();
+        wrong_cells[2][BYTES_PER_CELL - 1] ^= 1;
+        assert_eq!(verify(&commitments, &cell_indices, &wrong_cells, &proofs), Ok(false));
+
+        let mut wrong_commitments = commitments.clone();
+        wrong_commitments.swap(0, 4);
+        assert_eq!(verify(&wrong_commitments, &cell_indices, &cells, &proofs), Ok(false));
+
+        // Invalid inputs are errors
+        assert_eq!(
+            verify(&commitments[1..], &cell_indices, &cells, &proofs),
+            Err(KzgError::LengthMismatch { expected: 8, actual: 7 })
+        );
+        let mut out_of_range = cell_indices.clone();
+        out_of_range[0] = CELLS_PER_EXT_BLOB as CellIndex;
+        assert_eq!(
+            verify(&commitments, &out_of_range, &cells, &proofs),
+            Err(KzgError::IndexOutOfRange { index: CELLS_PER_EXT_BLOB, size: CELLS_PER_EXT_BLOB })
+        );
+        let mut non_canonical = cells;
+        non_canonical[0][..SCALAR_SERIALIZED_SIZE].fill(0xff);
+        assert_eq!(verify(&commitments, &cell_indices, &non_canonical, &proofs), Err(KzgError::NonCanonicalScalar));
+    }
+
+    #[test]
+    fn cells_and_proofs() {
+        let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+        let extended_domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_EXT_BLOB);
+        let public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+
+        let blob = random_blob();
+        let commitment = bytes_to_g1(&blob_to_kzg_commitment(&public_parameters, &blob).unwrap()).unwrap();
+        let (cells, proofs) = compute_cells_and_kzg_proofs(&public_parameters, &domain, &extended_domain, &blob).unwrap();
+        assert_eq!(cells, compute_cells(&domain, &extended_domain, &blob).unwrap());
+        assert_eq!(cells.len(), CELLS_PER_EXT_BLOB);
+        assert_eq!(proofs.len(), CELLS_PER_EXT_BLOB);
+
+        // The first half of the cells is the blob
+        assert_eq!(cells[..CELLS_PER_EXT_BLOB / 2].concat(), blob.to_vec());
+
+        // Check a proof with the secret: p(tau) - I(tau) = q(tau) * Z(tau)
+        let tau = blstrs::Scalar::from(123456789u64);
+        let cell_index = 5;
+        let coset = coset_for_cell(&extended_domain, cell_index).unwrap();
+        let coset_evals = cell_to_coset_evals(&cells[cell_index as usize]).unwrap();
+
+        // The coset is `h * roots` in bit-reversed order
+        let cell_domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_CELL);
+        let interpolation = PolynomialCoeff::new(cell_domain.coset_ifft_scalars(coset_evals, coset[0]));
+        let interpolation_at_tau = interpolation.evaluate(tau);
+        let vanishing_at_tau = tau.pow_vartime([FIELD_ELEMENTS_PER_CELL as u64])
+            - coset[0].pow_vartime([FIELD_ELEMENTS_PER_CELL as u64]);
+
+        let proof = bytes_to_g1(&proofs[cell_index as usize]).unwrap();
+        let generator = blstrs::G1Projective::generator();
+        assert_eq!(
+            blstrs::G1Projective::from(commitment) - generator * interpolation_at_tau,
+            blstrs::G1Projective::from(proof) * vanishing_at_tau
+        );
+
+        // The proofs are the same when they are computed with FK20
+        let mut public_parameters = public_parameters;
+        public_parameters.precompute_cell_proofs(&monomial_commit_key(tau), &extended_domain).unwrap();
+        assert_eq!(
+            compute_cells_and_kzg_proofs(&public_parameters, &domain, &extended_domain, &blob).unwrap(),
+            (cells, proofs)
+        );
+
+        // The tables can only be precomputed for the bit-reversed domain of an extended blob
+        let commit_key = monomial_commit_key(tau);
+        assert_eq!(
+            public_parameters.precompute_cell_proofs(&commit_key, &Domain::new(FIELD_ELEMENTS_PER_EXT_BLOB)),
+            Err(KzgError::NotBitReversed)
+        );
+        assert_eq!(
+            public_parameters.precompute_cell_proofs(&commit_key, &domain),
+            Err(KzgError::LengthMismatch { expected: FIELD_ELEMENTS_PER_EXT_BLOB, actual: FIELD_ELEMENTS_PER_BLOB })
+        );
+    }
+
+    #[test]
+    fn recover_cells_and_proofs() {
+        let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+        let extended_domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_EXT_BLOB);
+        let mut public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+        public_parameters
+            .precompute_cell_proofs(&monomial_commit_key(blstrs::Scalar::from(123456789u64)), &extended_domain)
+            .unwrap();
+        let recover = |cell_indices: &[CellIndex], cells: &[Cell]| {
+            recover_cells_and_kzg_proofs(&public_parameters, &domain, &extended_domain, cell_indices, cells)
+        };
+
+        let blob = random_blob();
+        let commitment = blob_to_kzg_commitment(&public_parameters, &blob).unwrap();
+        let (cells, proofs) = compute_cells_and_kzg_proofs(&public_parameters, &domain, &extended_domain, &blob).unwrap();
+
+        // Recover from a random half of the cells
+        let mut cell_indices: Vec<CellIndex> = (0..CELLS_PER_EXT_BLOB as CellIndex).collect();
+        cell_indices.shuffle(&mut rand::thread_rng());
+        let known_indices = &cell_indices[..CELLS_PER_EXT_BLOB / 2];
+        let known_cells: Vec<_> = known_indices.iter().map(|cell_index| cells[*cell_index as usize]).collect();
+
+        let (recovered_cells, recovered_proofs) = recover(known_indices, &known_cells).unwrap();
+        assert_eq!(recovered_cells, cells);
+        assert_eq!(recovered_proofs, proofs);
+
+        // The recovered cells and proofs verify against the original commitment
+        let all_indices: Vec<CellIndex> = (0..CELLS_PER_EXT_BLOB as CellIndex).collect();
+        let commitments = vec![commitment; CELLS_PER_EXT_BLOB];
+        assert_eq!(
+            verify_cell_kzg_proof_batch(
+                &public_parameters,
+                &domain,
+                &extended_domain,
+                &commitments,
+                &all_indices,
+                &recovered_cells,
+                &recovered_proofs,
+            ),
+            Ok(true)
+        );
+
+        // Invalid inputs are errors
+        assert_eq!(
+            recover(&known_indices[1..], &known_cells[1..]),
+            Err(KzgError::TooFewPoints { minimum: CELLS_PER_EXT_BLOB / 2, actual: CELLS_PER_EXT_BLOB / 2 - 1 })
+        );
+        assert_eq!(
+            recover(&known_indices[1..], &known_cells),
+            Err(KzgError::LengthMismatch { expected: CELLS_PER_EXT_BLOB / 2 - 1, actual: CELLS_PER_EXT_BLOB / 2 })
+        );
+        let mut duplicate_indices = known_indices.to_vec();
+        duplicate_indices[1] = duplicate_indices[0];
+        assert_eq!(
+            recover(&duplicate_indices, &known_cells),
+            Err(KzgError::DuplicateIndex(duplicate_indices[0] as usize))
+        );
+
+        // With more than half of the cells, a wrong cell is detected
+        let mut extra_indices = cell_indices[..CELLS_PER_EXT_BLOB / 2 + 1].to_vec();
+        let mut extra_cells: Vec<_> = extra_indices.iter().map(|cell_index| cells[*cell_index as usize]).collect();
+        extra_cells[0][BYTES_PER_CELL - 1] ^= 1;
+        assert_eq!(recover(&extra_indices, &extra_cells), Err(KzgError::InconsistentSamples));
+        extra_indices[0] = CELLS_PER_EXT_BLOB as CellIndex;
+        assert_eq!(
+            recover(&extra_indices, &extra_cells),
+            Err(KzgError::IndexOutOfRange { index: CELLS_PER_EXT_BLOB, size: CELLS_PER_EXT_BLOB })
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+
+/// Errors returned by the fallible `try_*` functions in this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KzgError {
+    /// Two inputs which must have the same length do not
+    LengthMismatch { expected: usize, actual: usize },
+    /// The size must be a power of two
+    NotPowerOfTwo(usize),
+    /// There are no roots of unity of order `2^log_size`, since it exceeds the two-adicity of the field
+    DomainTooLarge { log_size: u32, two_adicity: u32 },
+    /// Fewer points were supplied than are needed
+    TooFewPoints { minimum: usize, actual: usize },
+    /// One of the elements being inverted is zero
+    ZeroInversion,
+    /// The bytes do not encode an integer less than the BLS modulus
+    NonCanonicalScalar,
+    /// The bytes do not encode a point on the curve and in the correct subgroup
+    MalformedPoint,
+    /// The first byte of a versioned hash is not a version that we support
+    UnsupportedVersion(u8),
+    /// An index is not less than the number of elements it indexes into
+    IndexOutOfRange { index: usize, size: usize },
+    /// The same index was given more than once
+    DuplicateIndex(usize),
+    /// The point at this position in the input was already given earlier in the input
+    DuplicatePoint(usize),
+    /// The samples are not all evaluations of one polynomial of low enough degree
+    InconsistentSamples,
+    /// The domain must store its roots in bit-reversed order
+    NotBitReversed,
+}
+
+impl fmt::Display for KzgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KzgError::LengthMismatch { expected, actual } => {
+                write!(f, "length mismatch, expected {expected} but got {actual}")
+            }
+            KzgError::NotPowerOfTwo(size) => {
+                write!(f, "the domain size must be a power of two, size is : {size}")
+            }
+            KzgError::DomainTooLarge { log_size, two_adicity } => {
+                write!(f, "two adicity is {two_adicity} but group size needed is 2^{log_size}")
+            }
+            KzgError::TooFewPoints { minimum, actual } => {
+                write!(f, "at least {minimum} points are needed, got {actual}")
+            }
+            KzgError::ZeroInversion => write!(f, "inversion by zero is not allowed"),
+            KzgError::NonCanonicalScalar => write!(f, "scalar is not less than the BLS modulus"),
+            KzgError::MalformedPoint => write!(f, "point is not on the curve or not in the correct subgroup"),
+            KzgError::UnsupportedVersion(version) => write!(f, "unsupported versioned hash version : {version:#04x}"),
+            KzgError::IndexOutOfRange { index, size } => write!(f, "index {index} is out of range for size {size}"),
+            KzgError::DuplicateIndex(index) => write!(f, "index {index} was given more than once"),
+            KzgError::DuplicatePoint(position) => write!(f, "the point at position {position} was already given"),
+            KzgError::InconsistentSamples => {
+                write!(f, "the samples are not evaluations of a polynomial of low enough degree")
+            }
+            KzgError::NotBitReversed => write!(f, "the domain is not in bit-reversed order"),
+        }
+    }
+}
+
+impl std::error::Error for KzgError {}
@@ -0,0 +1,154 @@
+use blst::{blst_p1, blst_p1_affine, limb_t};
+use group::{prime::PrimeCurveAffine, Group};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+// The number of bits needed to represent a scalar
+const SCALAR_NUM_BITS: usize = 255;
+
+/// A multi-scalar multiplication where the points are known ahead of time.
+///
+/// For every point `P_i`, we store the multiples `{ 1 * P_i, 2 * P_i, ..., 2^(window_size - 1) * P_i }`.
+/// Scalars are split into windows of `window_size` bits, which are recoded into signed digits,
+/// so each window needs one table lookup and one addition per point, and the doublings between
+/// windows are shared between all of the points.
+///
+/// The tables take up `num_points * 2^(window_size - 1)` affine points, which is 48MiB
+/// for 4096 points and a window size of 8.
+pub struct FixedBaseMsm {
+    window_size: usize,
+    num_points: usize,
+    // The multiples of point `i` are stored at `[i * 2^(window_size - 1), (i + 1) * 2^(window_size - 1))`
+    table: Vec<blst_p1_affine>,
+}
+
+impl FixedBaseMsm {
+    /// The largest window size that can be used. Larger windows would need tables that are too big to be practical
+    pub const MAX_WINDOW_SIZE: usize = 16;
+
+    /// Panics, if `window_size` is 0 or larger than `MAX_WINDOW_SIZE`, or if any of the points are the identity
+    pub fn new(points: &[blstrs::G1Affine], window_size: usize) -> FixedBaseMsm {
+        assert!(
+            (1..=Self::MAX_WINDOW_SIZE).contains(&window_size),
+            "the window size must be between 1 and {}, window size is : {window_size}",
+            Self::MAX_WINDOW_SIZE
+        );
+        // blst does not handle the identity when computing the multiples
+        assert!(
+            points.iter().all(|point| !bool::from(point.is_identity())),
+            "cannot precompute tables for the identity point"
+        );
+
+        let num_points = points.len();
+        let table_len = num_points << (window_size - 1);
+        let mut table = vec![blst_p1_affine::default(); table_len];
+        if num_points > 0 {
+            // A null pointer after the first point tells blst that the points are stored contiguously
+            let points_ptr = [as_blst_affine(points).as_ptr(), std::ptr::null()];
+            unsafe {
+                debug_assert_eq!(
+                    blst::blst_p1s_mult_wbits_precompute_sizeof(window_size, num_points),
+                    table_len * std::mem::size_of::<blst_p1_affine>()
+                );
+                blst::blst_p1s_mult_wbits_precompute(table.as_mut_ptr(), window_size, points_ptr.as_ptr(), num_points);
+            }
+        }
+
+        FixedBaseMsm { window_size, num_points, table }
+    }
+
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Computes `\sum scalars_i * P_i`
+    ///
+    /// Panics, if the number of scalars is not equal to the number of points
+    pub fn msm(&self, scalars: &[blstrs::Scalar]) -> blstrs::G1Projective {
+        assert_eq!(
+            scalars.len(),
+            self.num_points,
+            "the number of scalars must equal the number of precomputed points"
+        );
+
+        // Each thread accumulates a contiguous range of the points, which are then summed
+        #[cfg(feature = "parallel")]
+        {
+            let chunk_size = crate::utils::parallel_chunk_size(scalars.len().max(1));
+            scalars
+                .par_chunks(chunk_size)
+                .enumerate()
+                .map(|(chunk_index, scalars)| self.msm_range(chunk_index * chunk_size, scalars))
+                .reduce(blstrs::G1Projective::identity, |a, b| a + b)
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        self.msm_range(0, scalars)
+    }
+
+    // Computes `\sum scalars_i * P_{offset + i}`
+    fn msm_range(&self, offset: usize, scalars: &[blstrs::Scalar]) -> blstrs::G1Projective {
+        let num_points = scalars.len();
+        if num_points == 0 {
+            return blstrs::G1Projective::identity();
+        }
+
+        let scalar_bytes: Vec<u8> = scalars.iter().flat_map(|scalar| scalar.to_bytes_le()).collect();
+        // A null pointer after the first scalar tells blst that the scalars are stored contiguously
+        let scalars_ptr = [scalar_bytes.as_ptr(), std::ptr::null()];
+
+        let table = &self.table[offset << (self.window_size - 1)..];
+
+        let mut result = blstrs::G1Projective::identity();
+        unsafe {
+            let scratch_size = blst::blst_p1s_mult_wbits_scratch_sizeof(num_points);
+            let mut scratch = vec![0 as limb_t; scratch_size.div_ceil(std::mem::size_of::<limb_t>())];
+            blst::blst_p1s_mult_wbits(
+                // `G1Projective` is a transparent wrapper around `blst_p1`
+                &mut result as *mut blstrs::G1Projective as *mut blst_p1,
+                table.as_ptr(),
+                self.window_size,
+                num_points,
+                scalars_ptr.as_ptr(),
+                SCALAR_NUM_BITS,
+                scratch.as_mut_ptr(),
+            );
+        }
+
+        result
+    }
+}
+
+// `G1Affine` is a transparent wrapper around `blst_p1_affine`
+fn as_blst_affine(points: &[blstrs::G1Affine]) -> &[blst_p1_affine] {
+    unsafe { std::slice::from_raw_parts(points.as_ptr() as *const blst_p1_affine, points.len()) }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ff::Field;
+    use group::Curve;
+
+    use super::*;
+    use crate::commit_key::g1_lincomb;
+
+    #[test]
+    fn fixed_base_msm_matches_lincomb() {
+        let num_points = 9;
+        let points: Vec<_> = (0..num_points)
+            .map(|_| blstrs::G1Projective::random(&mut rand::thread_rng()).to_affine())
+            .collect();
+
+        // Include the edge cases zero, one and the largest scalar
+        let mut scalars: Vec<_> = (0..num_points - 3).map(|_| blstrs::Scalar::random(&mut rand::thread_rng())).collect();
+        scalars.extend([blstrs::Scalar::zero(), blstrs::Scalar::one(), -blstrs::Scalar::one()]);
+
+        let expected = g1_lincomb(&points, &scalars);
+        for window_size in [1, 2, 5, 8] {
+            let msm = FixedBaseMsm::new(&points, window_size);
+            assert_eq!(msm.msm(&scalars).to_affine(), expected);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use ff::Field;
+use group::{prime::PrimeCurveAffine, Curve, Group};
+
+use crate::{
+    commit_key::{g1_lincomb, CommitKey},
+    domain::Domain,
+    error::KzgError,
+    polynomial_coeff::PolynomialCoeff,
+};
+
+/// Computes the KZG proofs for every coset of a domain at once, using the algorithm from
+/// "Fast amortized KZG proofs" by Feist and Khovratovich (FK20).
+///
+/// The cosets are consecutive chunks of `coset_size` roots of the domain, which are cosets of the
+/// subgroup of order `coset_size` when the domain is bit-reversed. With a `coset_size` of one, these
+/// are the single-point proofs for every root of the domain, in any order.
+///
+/// The proof for the coset `h * <\mu>` is a commitment to the quotient of `f(X)` by `X^l - h^l`,
+/// where `l` is the coset size. For a polynomial with `n` coefficients, its coefficients are
+/// `\sum_s h^{ls} * c_s`, where the `c_s` are sums of the SRS points weighted by the coefficients of `f`
+/// and only depend on `f`. The `c_s` are computed with `l` Toeplitz matrix-vector products of size `n / l`,
+/// which are embedded in circulant matrices and computed with FFTs. The proofs are then a single FFT of the `c_s`.
+///
+/// This takes `O(n log n)` group operations, instead of one multi-scalar multiplication per coset.
+pub struct Fk20 {
+    coset_size: usize,
+    // The number of points in the commit key, which bounds the number of coefficients of the polynomial
+    num_coeffs: usize,
+    // The domain of size `2 * num_coeffs / coset_size` used to compute the Toeplitz products
+    circulant_domain: Domain,
+    // The roots of this domain are `h^l` for the shift `h` of each coset, in the same order as the cosets
+    proof_domain: Domain,
+    // `srs_ffts[j][r]` is evaluation `j` of the FFT of the points `{ \tau^{tl + r} G }` over the circulant domain
+    srs_ffts: Vec<Vec<blstrs::G1Affine>>,
+}
+
+impl Fk20 {
+    /// Panics, if the sizes are not valid. See `try_new` for a non-panicking version
+    pub fn new(commit_key: &CommitKey, domain: &Domain, coset_size: usize) -> Fk20 {
+        Fk20::try_new(commit_key, domain, coset_size).unwrap_or_else(|err| panic!("cannot initialize `Fk20`: {err}"))
+    }
+
+    /// Precomputes the FFTs of the points in `commit_key`, for computing proofs for the cosets of size
+    /// `coset_size` in `domain`
+    ///
+    /// The number of points in the commit key and the coset size must be powers of two,
+    /// and the coset size cannot be larger than either of them or the domain.
+    pub fn try_new(commit_key: &CommitKey, domain: &Domain, coset_size: usize) -> Result<Fk20, KzgError> {
+        let points = commit_key.points();
+        let num_coeffs = points.len();
+
+        for size in [coset_size, num_coeffs] {
+            if !size.is_power_of_two() {
+                return Err(KzgError::NotPowerOfTwo(size));
+            }
+        }
+        for size in [num_coeffs, domain.size()] {
+            if size < coset_size {
+                return Err(KzgError::TooFewPoints { minimum: coset_size, actual: size });
+            }
+        }
+
+        let num_rows = num_coeffs / coset_size;
+        let circulant_domain = Domain::try_new(2 * num_rows)?;
+        let proof_domain = if domain.is_bit_reversed() {
+            Domain::try_new_bit_reversed(domain.size() / coset_size)?
+        } else {
+            Domain::try_new(domain.size() / coset_size)?
+        };
+
+        // Column `r` holds the points `\tau^{tl + r} G`, for `t` less than `num_rows - 1`
+        let columns: Vec<_> = (0..coset_size)
+            .map(|r| {
+                let column = points.iter().skip(r).step_by(coset_size).take(num_rows - 1).copied().collect();
+                circulant_domain.fft_g1(column)
+            })
+            .collect();
+
+        let srs_ffts = (0..circulant_domain.size())
+            .map(|j| columns.iter().map(|column| column[j]).collect())
+            .collect();
+
+        Ok(Fk20 { coset_size, num_coeffs, circulant_domain, proof_domain, srs_ffts })
+    }
+
+    pub fn coset_size(&self) -> usize {
+        self.coset_size
+    }
+
+    /// The number of cosets, which is the number of proofs returned by `compute_proofs`
+    pub fn num_cosets(&self) -> usize {
+        self.proof_domain.size()
+    }
+
+    /// Computes the proofs for every coset, in the same order as the cosets in the domain
+    ///
+    /// Panics, if the polynomial has more coefficients than the commit key has points.
+    /// See `try_compute_proofs` for a non-panicking version
+    pub fn compute_proofs(&self, polynomial: &PolynomialCoeff) -> Vec<blstrs::G1Affine> {
+        self.try_compute_proofs(polynomial).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_compute_proofs(&self, polynomial: &PolynomialCoeff) -> Result<Vec<blstrs::G1Affine>, KzgError> {
+        let num_coeffs = polynomial.coeffs.len();
+        if num_coeffs > self.num_coeffs {
+            return Err(KzgError::TooFewPoints { minimum: num_coeffs, actual: self.num_coeffs });
+        }
+        let coeff = |index: usize| polynomial.coeffs.get(index).copied().unwrap_or(blstrs::Scalar::zero());
+
+        let num_rows = self.num_coeffs / self.coset_size;
+
+        // Column `r` holds the coefficients `f_{ml + r}` in reverse order, so that the
+        // Toeplitz products become the convolutions of the columns with the SRS columns
+        let coeff_ffts: Vec<_> = (0..self.coset_size)
+            .map(|r| {
+                let column = (0..num_rows).map(|i| coeff((num_rows - 1 - i) * self.coset_size + r)).collect();
+                self.circulant_domain.fft_scalars(column)
+            })
+            .collect();
+
+        // The convolutions are summed over all of the columns, so each evaluation is a multi-scalar multiplication
+        let products = self
+            .srs_ffts
+            .iter()
+            .enumerate()
+            .map(|(j, srs_fft)| {
+                let scalars: Vec<_> = coeff_ffts.iter().map(|coeff_fft| coeff_fft[j]).collect();
+                g1_lincomb(srs_fft, &scalars)
+            })
+            .collect();
+        let convolution = self.circulant_domain.ifft_g1(products);
+
+        // `c_s` is entry `num_rows - 2 - s` of the convolution. If there are fewer cosets than the number
+        // of `c_s`, the `h^{ls}` repeat, so the `c_s` are folded together
+        let mut quotient_coeffs = vec![blstrs::G1Projective::identity(); self.num_cosets()];
+        for s in 0..num_rows - 1 {
+            quotient_coeffs[s % self.num_cosets()] += convolution[num_rows - 2 - s];
+        }
+        let mut quotient_coeffs_affine = vec![blstrs::G1Affine::identity(); quotient_coeffs.len()];
+        blstrs::G1Projective::batch_normalize(&quotient_coeffs, &mut quotient_coeffs_affine);
+
+        Ok(self.proof_domain.fft_g1(quotient_coeffs_affine))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monomial_srs(size: usize) -> Vec<blstrs::G1Affine> {
+        let secret = blstrs::Scalar::from(1234567u64);
+        (0..size)
+            .map(|index| (blstrs::G1Affine::generator() * secret.pow_vartime([index as u64])).to_affine())
+            .collect()
+    }
+
+    // Commits to `polynomial` in monomial form
+    fn commit(srs: &[blstrs::G1Affine], polynomial: &PolynomialCoeff) -> blstrs::G1Affine {
+        if polynomial.coeffs.is_empty() {
+            return blstrs::G1Affine::identity();
+        }
+        g1_lincomb(&srs[..polynomial.coeffs.len()], &polynomial.coeffs)
+    }
+
+    fn random_polynomial(num_coeffs: usize) -> PolynomialCoeff {
+        PolynomialCoeff::new((0..num_coeffs).map(|_| blstrs::Scalar::random(&mut rand::thread_rng())).collect())
+    }
+
+    #[test]
+    fn single_point_proofs() {
+        let num_coeffs = 16;
+        let srs = monomial_srs(num_coeffs);
+        let commit_key = CommitKey::new(srs.clone());
+        let polynomial = random_polynomial(num_coeffs);
+
+        for domain in [Domain::new(num_coeffs), Domain::new_bit_reversed(2 * num_coeffs)] {
+            let proofs = Fk20::new(&commit_key, &domain, 1).compute_proofs(&polynomial);
+
+            assert_eq!(proofs.len(), domain.size());
+            for (root, proof) in domain.roots().iter().zip(proofs) {
+                let (quotient, _) = polynomial.divide_by_linear(*root);
+                assert_eq!(proof, commit(&srs, &quotient));
+            }
+        }
+    }
+
+    #[test]
+    fn coset_proofs() {
+        let num_coeffs = 32;
+        let srs = monomial_srs(num_coeffs);
+        let commit_key = CommitKey::new(srs.clone());
+        let polynomial = random_polynomial(num_coeffs - 3);
+
+        for (domain_size, coset_size) in [(64, 4), (64, 32), (8, 4)] {
+            let domain = Domain::new_bit_reversed(domain_size);
+            let fk20 = Fk20::new(&commit_key, &domain, coset_size);
+            let proofs = fk20.compute_proofs(&polynomial);
+
+            assert_eq!(fk20.num_cosets(), domain_size / coset_size);
+            for (coset, proof) in domain.roots().chunks_exact(coset_size).zip(proofs) {
+                let shift_pow = coset[0].pow_vartime([coset_size as u64]);
+                let (quotient, _) = polynomial.divide_by_binomial(coset_size, shift_pow);
+                assert_eq!(proof, commit(&srs, &quotient));
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_inputs_are_rejected() {
+        let commit_key = CommitKey::new(monomial_srs(8));
+        let domain = Domain::new(8);
+
+        assert!(matches!(Fk20::try_new(&commit_key, &domain, 3), Err(KzgError::NotPowerOfTwo(3))));
+        assert!(matches!(
+            Fk20::try_new(&CommitKey::new(monomial_srs(6)), &domain, 2),
+            Err(KzgError::NotPowerOfTwo(6))
+        ));
+        assert!(matches!(
+            Fk20::try_new(&commit_key, &Domain::new(4), 8),
+            Err(KzgError::TooFewPoints { minimum: 8, actual: 4 })
+        ));
+
+        let fk20 = Fk20::new(&commit_key, &domain, 2);
+        assert_eq!(
+            fk20.try_compute_proofs(&random_polynomial(9)),
+            Err(KzgError::TooFewPoints { minimum: 9, actual: 8 })
+        );
+    }
+}
@@ -1,11 +1,22 @@
 
 pub mod domain;
+pub mod coset_domain;
+pub mod error;
 pub mod commit_key;
+pub mod fixed_base_msm;
+pub mod fk20;
 pub mod opening_key;
 pub mod polynomial;
+pub mod polynomial_coeff;
 pub mod proof;
 pub mod params;
 pub mod utils;
+pub mod eip4844;
+pub mod blob_encoding;
+pub mod das;
+pub mod eip7594;
+pub mod precompile;
+pub mod trusted_setup;
 
 // The number of bytes needed to represent a scalar
 pub const SCALAR_SERIALIZED_SIZE: usize = 32;
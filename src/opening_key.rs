@@ -2,6 +2,8 @@
 use pairing_lib::{group::Group, MillerLoopResult, MultiMillerLoop};
 use blstrs::{Bls12, G2Prepared};
 
+use crate::{commit_key::g1_lincomb, error::KzgError, polynomial_coeff::PolynomialCoeff, utils};
+
 /// Opening Key is used to verify opening proofs made about a committed polynomial.
 #[derive(Clone, Debug)]
 pub struct OpeningKey {
@@ -15,14 +17,30 @@ pub struct OpeningKey {
     pub prepared_g2: G2Prepared,
     /// \tau times the above generator of G2, prepared for use in pairings.
     pub prepared_beta_g2: G2Prepared,
+    /// Powers of \tau times the generator of G2, ie. `{ \tau^i G2 }`.
+    /// The first two elements are `g2_gen` and `tau_g2_gen`.
+    /// Polynomials with up to this many coefficients can be committed to in G2, see `commit_g2`.
+    pub g2_monomial: Vec<blstrs::G2Affine>,
 }
 
 impl OpeningKey {
     pub fn new(g1_gen: blstrs::G1Affine, g2_gen: blstrs::G2Affine, tau_g2_gen: blstrs::G2Affine) -> OpeningKey {
+        OpeningKey::from_g2_monomial(g1_gen, vec![g2_gen, tau_g2_gen])
+    }
+
+    /// Creates an opening key from the powers of \tau in G2, which is needed to verify
+    /// proofs for more than one point at a time.
+    ///
+    /// Panics, if there are fewer than two powers of \tau
+    pub fn from_g2_monomial(g1_gen: blstrs::G1Affine, g2_monomial: Vec<blstrs::G2Affine>) -> OpeningKey {
+        assert!(g2_monomial.len() >= 2, "at least two G2 points are needed, got {}", g2_monomial.len());
+        let g2_gen = g2_monomial[0];
+        let tau_g2_gen = g2_monomial[1];
+
         // Store cached elements for verifying multiple proofs.
         let prepared_g2 = G2Prepared::from(g2_gen);
         let prepared_beta_g2 = G2Prepared::from(tau_g2_gen);
-        OpeningKey { g1_gen, g2_gen, tau_g2_gen, prepared_g2, prepared_beta_g2, }
+        OpeningKey { g1_gen, g2_gen, tau_g2_gen, prepared_g2, prepared_beta_g2, g2_monomial, }
     }
 
     /// Checks that a polynomial `p` was evaluated at a point `z` and returned the value specified `y`.
@@ -45,4 +63,173 @@ impl OpeningKey {
 
         pairing.is_identity().into()
     }
+
+    /// Commits to `poly` in G2, using the powers of \tau in `g2_monomial`
+    ///
+    /// Returns an error, if the polynomial has more coefficients than there are powers of \tau
+    pub fn commit_g2(&self, poly: &PolynomialCoeff) -> Result<blstrs::G2Affine, KzgError> {
+        let num_coeffs = poly.coeffs.len();
+        if num_coeffs > self.g2_monomial.len() {
+            return Err(KzgError::TooFewPoints { minimum: num_coeffs, actual: self.g2_monomial.len() });
+        }
+        Ok(g2_lincomb(&self.g2_monomial[..num_coeffs], &poly.coeffs))
+    }
+
+    /// Checks that a polynomial `p` evaluates to `output_points[i]` at `input_points[i]` for every `i`,
+    /// where `quotient_comm` is a commitment to `(p(X) - I(X)) / Z_S(X)`.
+    ///
+    /// `I(X)` interpolates the output points over the input points `S`, and `Z_S(X)` vanishes on `S`.
+    /// Both are committed to in G2, so that the check is `e(C, G2) = e(G1, [I(\tau)]) * e(W, [Z_S(\tau)])`,
+    /// and there can be at most `g2_monomial.len() - 1` input points.
+    ///
+    /// Returns an error, if the number of input and output points differ, if an input point is repeated,
+    /// or if there are too many input points
+    pub fn verify_multi_point(
+        &self,
+        input_points: &[blstrs::Scalar],
+        output_points: &[blstrs::Scalar],
+        poly_comm: blstrs::G1Affine,
+        quotient_comm: blstrs::G1Affine,
+    ) -> Result<bool, KzgError> {
+        if input_points.len() != output_points.len() {
+            return Err(KzgError::LengthMismatch { expected: input_points.len(), actual: output_points.len() });
+        }
+        utils::check_distinct_points(input_points)?;
+
+        let vanishing_comm = self.commit_g2(&PolynomialCoeff::vanishing(input_points))?;
+        let interpolation = PolynomialCoeff::interpolate(input_points, output_points)?;
+        let interpolation_comm = self.commit_g2(&interpolation)?;
+
+        // e(C, G2) * e(-G1, [I(\tau)]) * e(-W, [Z_S(\tau)]) == 1
+        let neg_g1_gen = -self.g1_gen;
+        let neg_quotient_comm = -quotient_comm;
+        let prepared_interpolation_comm = G2Prepared::from(interpolation_comm);
+        let prepared_vanishing_comm = G2Prepared::from(vanishing_comm);
+
+        let terms = [
+            (&poly_comm, &self.prepared_g2),
+            (&neg_g1_gen, &prepared_interpolation_comm),
+            (&neg_quotient_comm, &prepared_vanishing_comm),
+        ];
+        let pairing = Bls12::multi_miller_loop(&terms).final_exponentiation();
+
+        Ok(pairing.is_identity().into())
+    }
+
+    /// Checks that for every `i`, the polynomial committed to in `poly_comms[i]` evaluates to
+    /// `output_points[i]` at `input_points[i]`.
+    ///
+    /// The individual pairing checks are folded into a single one, using powers of `challenge`.
+    /// `challenge` must be derived from all of the inputs, otherwise an invalid proof can be
+    /// cancelled out by another invalid proof.
+    ///
+    /// Returns an error, if the number of input points, output points, commitments and witnesses differ.
+    pub fn verify_batch(
+        &self,
+        input_points: &[blstrs::Scalar],
+        output_points: &[blstrs::Scalar],
+        poly_comms: &[blstrs::G1Affine],
+        witness_comms: &[blstrs::G1Affine],
+        challenge: blstrs::Scalar,
+    ) -> Result<bool, KzgError> {
+        let num_proofs = poly_comms.len();
+        for other_len in [input_points.len(), output_points.len(), witness_comms.len()] {
+            if other_len != num_proofs {
+                return Err(KzgError::LengthMismatch { expected: num_proofs, actual: other_len });
+            }
+        }
+        if num_proofs == 0 {
+            return Ok(true);
+        }
+
+        let r_powers = utils::compute_powers(challenge, num_proofs);
+
+        // sum r^i * W_i
+        let proof_lincomb = g1_lincomb(witness_comms, &r_powers);
+
+        // sum r^i * z_i * W_i
+        let r_times_z: Vec<_> = r_powers.iter().zip(input_points).map(|(r_i, z_i)| r_i * z_i).collect();
+        let proof_z_lincomb = g1_lincomb(witness_comms, &r_times_z);
+
+        // sum r^i * (C_i - y_i * G) = sum r^i * C_i - (sum r^i * y_i) * G
+        let comm_lincomb = g1_lincomb(poly_comms, &r_powers);
+        let y_lincomb: blstrs::Scalar = r_powers.iter().zip(output_points).map(|(r_i, y_i)| r_i * y_i).sum();
+
+        // e(sum r^i * (C_i - y_i * G + z_i * W_i), G2) * e(-sum r^i * W_i, \tau * G2) == 1
+        let inner_a: blstrs::G1Affine = (comm_lincomb - (self.g1_gen * y_lincomb) + proof_z_lincomb).into();
+        let inner_b: blstrs::G1Affine = -proof_lincomb;
+
+        let terms = [(&inner_a, &self.prepared_g2), (&inner_b, &self.prepared_beta_g2)];
+        let pairing = Bls12::multi_miller_loop(&terms).final_exponentiation();
+
+        Ok(pairing.is_identity().into())
+    }
+}
+
+/// A multi-scalar multiplication in G2
+///
+/// Panics, if the number of points and scalars differ
+pub fn g2_lincomb(points: &[blstrs::G2Affine], scalars: &[blstrs::Scalar]) -> blstrs::G2Affine {
+    assert_eq!(points.len(), scalars.len(), "the number of points and scalars must be equal");
+    // blst does not handle an empty multi-scalar multiplication
+    if points.is_empty() {
+        return blstrs::G2Projective::identity().into();
+    }
+
+    let points: Vec<_> = points.iter().map(blstrs::G2Projective::from).collect();
+    blstrs::G2Projective::multi_exp(&points, scalars).into()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ff::Field;
+
+    use crate::{domain::Domain, error::KzgError, params::PublicParameters, polynomial::Polynomial, proof::Proof};
+
+    fn random_vector(length: usize) -> Vec<blstrs::Scalar> {
+        (0..length).map(|_| blstrs::Scalar::random(&mut rand::thread_rng())).collect()
+    }
+
+    #[test]
+    fn verify_batch_matches_individual_verification() {
+        let size = 2usize.pow(4);
+        let num_proofs = 5;
+
+        let domain = Domain::new(size);
+        let public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+
+        let input_points = random_vector(num_proofs);
+        let proofs: Vec<_> = input_points
+            .iter()
+            .map(|input_point| {
+                let poly = Polynomial::new(random_vector(size));
+                let poly_comm = public_parameters.commit_key.commit(&poly);
+                Proof::create(&public_parameters.commit_key, &poly, poly_comm, *input_point, &domain)
+            })
+            .collect();
+
+        let mut output_points: Vec<_> = proofs.iter().map(|proof| proof.output_point).collect();
+        let poly_comms: Vec<_> = proofs.iter().map(|proof| proof.polynomial_commitment).collect();
+        let witness_comms: Vec<_> = proofs.iter().map(|proof| proof.quotient_commitment).collect();
+
+        let opening_key = &public_parameters.opening_key;
+        let challenge = blstrs::Scalar::random(&mut rand::thread_rng());
+        assert_eq!(
+            opening_key.verify_batch(&input_points, &output_points, &poly_comms, &witness_comms, challenge),
+            Ok(true)
+        );
+        assert_eq!(opening_key.verify_batch(&[], &[], &[], &[], challenge), Ok(true));
+        assert_eq!(
+            opening_key.verify_batch(&input_points[1..], &output_points, &poly_comms, &witness_comms, challenge),
+            Err(KzgError::LengthMismatch { expected: num_proofs, actual: num_proofs - 1 })
+        );
+
+        // A single wrong output point invalidates the whole batch
+        output_points[2] += blstrs::Scalar::one();
+        assert_eq!(
+            opening_key.verify_batch(&input_points, &output_points, &poly_comms, &witness_comms, challenge),
+            Ok(false)
+        );
+    }
 }
@@ -1,4 +1,20 @@
-use crate::{commit_key::*, opening_key::OpeningKey, domain::Domain};
+use std::path::Path;
+
+use crate::{
+    commit_key::*,
+    opening_key::OpeningKey,
+    domain::Domain,
+    eip7594::{FIELD_ELEMENTS_PER_CELL, FIELD_ELEMENTS_PER_EXT_BLOB},
+    error::KzgError,
+    fk20::Fk20,
+    trusted_setup::*,
+};
+
+// The number of powers of tau in G2, which matches the Ethereum trusted setup.
+//
+// Verifying a proof for a set of `k` points commits to their vanishing polynomial in G2, which has degree `k`,
+// so `k + 1` powers are needed. The largest sets that are opened are the 64 points of a cell.
+const NUM_G2_POWERS: usize = 65;
 
 // This is the SRS in lagrange form.
 //
@@ -6,6 +22,8 @@ use crate::{commit_key::*, opening_key::OpeningKey, domain::Domain};
 pub struct PublicParameters {
     pub commit_key: CommitKeyLagrange,
     pub opening_key: OpeningKey,
+    // Tables for computing the proofs for all of the cells of a blob at once, see `precompute_cell_proofs`
+    pub cell_proofs: Option<Fk20>,
 }
 
 impl PublicParameters {
@@ -16,17 +34,59 @@ impl PublicParameters {
         let tau_fr = blstrs::Scalar::from(tau);
         let g1_gen = blstrs::G1Affine::generator();
         let g2_gen = blstrs::G2Affine::generator();
-        let tau_g2_gen = (g2_gen * tau_fr).into();
 
         let powers_of_tau_g1: Vec<blstrs::G1Affine> = (0..domain.size())
             .map(|index| {
-                let secret_exp = tau_fr.pow_vartime(&[index as u64]);
+                let secret_exp = tau_fr.pow_vartime([index as u64]);
                 (blstrs::G1Affine::generator() * secret_exp).into()
             })
             .collect();
 
-        let commit_key = CommitKey::new(powers_of_tau_g1).into_lagrange(&domain);
-        let opening_key = OpeningKey::new(g1_gen, g2_gen, tau_g2_gen);
-        PublicParameters { commit_key, opening_key }
+        let powers_of_tau_g2: Vec<blstrs::G2Affine> = (0..NUM_G2_POWERS)
+            .map(|index| (g2_gen * tau_fr.pow_vartime([index as u64])).into())
+            .collect();
+
+        let commit_key = CommitKey::new(powers_of_tau_g1).into_lagrange(domain);
+        let opening_key = OpeningKey::from_g2_monomial(g1_gen, powers_of_tau_g2);
+        PublicParameters { commit_key, opening_key, cell_proofs: None }
+    }
+
+    /// Loads the public parameters from a file in the Ethereum `trusted_setup.txt` format
+    ///
+    /// Blobs store their evaluations in bit-reversed order, so `domain` should be created
+    /// with `Domain::new_bit_reversed` to be compatible with them.
+    pub fn from_trusted_setup_file(path: impl AsRef<Path>, domain: &Domain) -> Result<Self, TrustedSetupError> {
+        let trusted_setup = TrustedSetup::from_file(path)?;
+        Ok(Self::from_trusted_setup(&trusted_setup, domain)?)
+    }
+
+    /// Builds the public parameters from an already parsed trusted setup
+    ///
+    /// Returns an error, if the size of `domain` is not the number of points in the setup.
+    pub fn from_trusted_setup(trusted_setup: &TrustedSetup, domain: &Domain) -> Result<Self, KzgError> {
+        let commit_key = trusted_setup.commit_key(domain)?;
+        let opening_key = trusted_setup.opening_key();
+        Ok(PublicParameters { commit_key, opening_key, cell_proofs: None })
+    }
+
+    /// Precomputes the tables used by `compute_cells_and_kzg_proofs` to compute the proofs for all of the
+    /// cells of a blob at once with FK20, instead of one multi-scalar multiplication per cell
+    ///
+    /// `commit_key` must be the monomial form of the commit key, see `TrustedSetup::monomial_commit_key`.
+    /// Returns an error, if `extended_domain` is not the bit-reversed domain of an extended blob,
+    /// or if the number of points in `commit_key` is not a power of two.
+    pub fn precompute_cell_proofs(&mut self, commit_key: &CommitKey, extended_domain: &Domain) -> Result<(), KzgError> {
+        if extended_domain.size() != FIELD_ELEMENTS_PER_EXT_BLOB {
+            return Err(KzgError::LengthMismatch {
+                expected: FIELD_ELEMENTS_PER_EXT_BLOB,
+                actual: extended_domain.size(),
+            });
+        }
+        if !extended_domain.is_bit_reversed() {
+            return Err(KzgError::NotBitReversed);
+        }
+
+        self.cell_proofs = Some(Fk20::try_new(commit_key, extended_domain, FIELD_ELEMENTS_PER_CELL)?);
+        Ok(())
     }
 }
@@ -1,6 +1,8 @@
-use crate::{domain::Domain, utils};
+use crate::{domain::Domain, error::KzgError, utils};
 
 use group::ff::Field;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 #[derive(Debug, Clone)]
 // Polynomial representation in evaluation form
@@ -26,20 +28,19 @@ impl std::ops::Index<usize> for &Polynomial {
 impl Polynomial {
     /// Panics, if the number of evaluations is 0 or not a power of two
     /// 0 is not a power of two, so we can remove it
+    ///
+    /// See `try_new` for a non-panicking version
     pub fn new(evaluations: Vec<blstrs::Scalar>) -> Polynomial {
-        // We could return an Option, users of the library
-        // who consume this API directly, will be forced to unwrap.
-        //
-        // We may change it in the future, as the further up the stack
-        // we put the unwrap, the more visible this is.
-
-        assert!(
-            evaluations.len().is_power_of_two(),
-            "the domain size must be a power of two, size is : {}",
-            evaluations.len()
-        );
+        Polynomial::try_new(evaluations).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Returns an error, if the number of evaluations is 0 or not a power of two
+    pub fn try_new(evaluations: Vec<blstrs::Scalar>) -> Result<Polynomial, KzgError> {
+        if !evaluations.len().is_power_of_two() {
+            return Err(KzgError::NotPowerOfTwo(evaluations.len()));
+        }
 
-        Polynomial { evaluations }
+        Ok(Polynomial { evaluations })
     }
 
     pub fn evaluate(&self, z: blstrs::Scalar, domain: &Domain) -> blstrs::Scalar {
@@ -61,14 +62,24 @@ impl Polynomial {
         let domain_size = domain.size();
 
         let mut denominator: Vec<_> = domain.roots().iter().map(|root_i| z - root_i).collect();
-        utils::serial_batch_inversion(&mut denominator);
+        utils::batch_inversion(&mut denominator);
 
-        let mut result = blstrs::Scalar::zero();
-        // TODO Use zip here on evals, domain and denominator
-        for i in 0..domain_size {
-            result += (self.evaluations[i] * domain[i]) * denominator[i];
-        }
-        result * (z.pow_vartime(&[domain_size as u64]) - blstrs::Scalar::one()) * domain.domain_size_inv
+        #[cfg(feature = "parallel")]
+        let result: blstrs::Scalar = (&self.evaluations, domain.roots(), &denominator)
+            .into_par_iter()
+            .map(|(eval_i, root_i, denominator_i)| (eval_i * root_i) * denominator_i)
+            .sum();
+
+        #[cfg(not(feature = "parallel"))]
+        let result: blstrs::Scalar = self
+            .evaluations
+            .iter()
+            .zip(domain.roots())
+            .zip(&denominator)
+            .map(|((eval_i, root_i), denominator_i)| (eval_i * root_i) * denominator_i)
+            .sum();
+
+        result * (z.pow_vartime([domain_size as u64]) - blstrs::Scalar::one()) * domain.domain_size_inv
     }
 
     fn num_evaluations(&self) -> usize {
@@ -0,0 +1,342 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::{domain::Domain, error::KzgError, polynomial::Polynomial, utils::try_batch_inversion};
+
+use group::ff::Field;
+
+#[derive(Debug, Clone, PartialEq)]
+// Polynomial representation in monomial form
+// `coeffs[i]` is the coefficient of `X^i`
+//
+// Trailing zero coefficients are allowed, so two polynomials which are equal
+// may not compare as equal unless they have been trimmed
+pub struct PolynomialCoeff {
+    pub coeffs: Vec<blstrs::Scalar>,
+}
+
+impl PolynomialCoeff {
+    pub fn new(coeffs: Vec<blstrs::Scalar>) -> PolynomialCoeff {
+        PolynomialCoeff { coeffs }
+    }
+
+    pub fn zero() -> PolynomialCoeff {
+        PolynomialCoeff { coeffs: Vec::new() }
+    }
+
+    /// Returns the polynomial `\prod (X - point_i)`, which is zero on all of `points`
+    pub fn vanishing(points: &[blstrs::Scalar]) -> PolynomialCoeff {
+        let mut coeffs = vec![blstrs::Scalar::one()];
+        for point in points {
+            // Multiply the current polynomial by (X - point)
+            coeffs.insert(0, blstrs::Scalar::zero());
+            for i in 0..coeffs.len() - 1 {
+                let shifted = coeffs[i + 1] * point;
+                coeffs[i] -= shifted;
+            }
+        }
+        PolynomialCoeff { coeffs }
+    }
+
+    /// Returns the polynomial of degree less than `points.len()`, which evaluates to `values[i]` at `points[i]`
+    ///
+    /// This uses Lagrange interpolation, which takes `O(n^2)` operations for `n` points.
+    ///
+    /// Returns an error, if the number of points and values differ, or if a point is repeated
+    pub fn interpolate(points: &[blstrs::Scalar], values: &[blstrs::Scalar]) -> Result<PolynomialCoeff, KzgError> {
+        if points.len() != values.len() {
+            return Err(KzgError::LengthMismatch { expected: points.len(), actual: values.len() });
+        }
+
+        // The i'th lagrange polynomial is `(Z(X) / (X - x_i)) / \prod_{j != i} (x_i - x_j)`,
+        // and the denominator is the numerator evaluated at `x_i`
+        let vanishing = PolynomialCoeff::vanishing(points);
+        let numerators: Vec<_> = points.iter().map(|point| vanishing.divide_by_linear(*point).0).collect();
+        let mut denominators: Vec<_> =
+            numerators.iter().zip(points).map(|(numerator, point)| numerator.evaluate(*point)).collect();
+        try_batch_inversion(&mut denominators)?;
+
+        let mut coeffs = vec![blstrs::Scalar::zero(); points.len()];
+        for ((numerator, denominator_inv), value) in numerators.iter().zip(&denominators).zip(values) {
+            let scale = value * denominator_inv;
+            for (coeff, numerator_coeff) in coeffs.iter_mut().zip(&numerator.coeffs) {
+                *coeff += numerator_coeff * scale;
+            }
+        }
+        Ok(PolynomialCoeff { coeffs })
+    }
+
+    /// Interpolates `poly` over `domain` to get its monomial form
+    pub fn from_polynomial(poly: &Polynomial, domain: &Domain) -> PolynomialCoeff {
+        PolynomialCoeff { coeffs: domain.ifft_scalars(poly.evaluations.clone()) }
+    }
+
+    /// Evaluates the polynomial over `domain` to get its evaluation form
+    ///
+    /// Panics, if the degree of the polynomial is not less than the domain size
+    pub fn to_polynomial(&self, domain: &Domain) -> Polynomial {
+        let mut coeffs = self.coeffs.clone();
+        coeffs.truncate(self.num_coeffs_trimmed());
+        Polynomial::new(domain.fft_scalars(coeffs))
+    }
+
+    /// Evaluates the polynomial at `z` using Horner's method
+    pub fn evaluate(&self, z: blstrs::Scalar) -> blstrs::Scalar {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(blstrs::Scalar::zero(), |result, coeff| result * z + coeff)
+    }
+
+    /// Returns the degree of the polynomial, ignoring trailing zero coefficients
+    ///
+    /// The zero polynomial is given degree 0
+    pub fn degree(&self) -> usize {
+        self.num_coeffs_trimmed().saturating_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num_coeffs_trimmed() == 0
+    }
+
+    /// Multiplies every coefficient by `scalar`
+    pub fn scale(&self, scalar: blstrs::Scalar) -> PolynomialCoeff {
+        PolynomialCoeff { coeffs: self.coeffs.iter().map(|coeff| coeff * scalar).collect() }
+    }
+
+    /// Divides the polynomial by `(X - z)` using synthetic division
+    ///
+    /// Returns the quotient and the remainder, where the remainder is equal to `p(z)`
+    pub fn divide_by_linear(&self, z: blstrs::Scalar) -> (PolynomialCoeff, blstrs::Scalar) {
+        if self.coeffs.is_empty() {
+            return (PolynomialCoeff::zero(), blstrs::Scalar::zero());
+        }
+
+        // Each coefficient of the quotient is the running value of Horner's method
+        let mut quotient = vec![blstrs::Scalar::zero(); self.coeffs.len() - 1];
+        let mut running_value = blstrs::Scalar::zero();
+        for i in (1..self.coeffs.len()).rev() {
+            running_value = running_value * z + self.coeffs[i];
+            quotient[i - 1] = running_value;
+        }
+        let remainder = running_value * z + self.coeffs[0];
+
+        (PolynomialCoeff { coeffs: quotient }, remainder)
+    }
+
+    /// Divides the polynomial by `(X^degree - constant)` using long division
+    ///
+    /// Returns the quotient and the remainder. The remainder has fewer than `degree` coefficients.
+    /// When `constant = h^degree`, the divisor vanishes on the coset `h * {\omega : \omega^degree = 1}`,
+    /// so the remainder is the polynomial that interpolates `p` over that coset.
+    ///
+    /// Panics, if `degree` is zero
+    pub fn divide_by_binomial(&self, degree: usize, constant: blstrs::Scalar) -> (PolynomialCoeff, PolynomialCoeff) {
+        assert!(degree > 0, "cannot divide by a constant polynomial");
+        if self.coeffs.len() <= degree {
+            return (PolynomialCoeff::zero(), self.clone());
+        }
+
+        let mut remainder = self.coeffs.clone();
+        let mut quotient = vec![blstrs::Scalar::zero(); self.coeffs.len() - degree];
+        for i in (degree..self.coeffs.len()).rev() {
+            // Subtract `remainder[i] * X^(i - degree) * (X^degree - constant)`
+            let leading_coeff = remainder[i];
+            quotient[i - degree] = leading_coeff;
+            remainder[i - degree] += leading_coeff * constant;
+        }
+        remainder.truncate(degree);
+
+        (PolynomialCoeff { coeffs: quotient }, PolynomialCoeff { coeffs: remainder })
+    }
+
+    /// Divides the polynomial by `\prod (X - point_i)`, by dividing by each `(X - point_i)` in turn
+    ///
+    /// Returns the quotient, and discards the remainder, which has degree less than `points.len()`
+    pub fn divide_by_vanishing(&self, points: &[blstrs::Scalar]) -> PolynomialCoeff {
+        points.iter().fold(self.clone(), |quotient, point| quotient.divide_by_linear(*point).0)
+    }
+
+    fn num_coeffs_trimmed(&self) -> usize {
+        self.coeffs
+            .iter()
+            .rposition(|coeff| !coeff.is_zero_vartime())
+            .map_or(0, |index| index + 1)
+    }
+}
+
+impl Add for &PolynomialCoeff {
+    type Output = PolynomialCoeff;
+
+    fn add(self, other: &PolynomialCoeff) -> PolynomialCoeff {
+        let (longer, shorter) = if self.coeffs.len() >= other.coeffs.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        let mut coeffs = longer.coeffs.clone();
+        for (coeff, other_coeff) in coeffs.iter_mut().zip(&shorter.coeffs) {
+            *coeff += other_coeff;
+        }
+        PolynomialCoeff { coeffs }
+    }
+}
+
+impl Sub for &PolynomialCoeff {
+    type Output = PolynomialCoeff;
+
+    fn sub(self, other: &PolynomialCoeff) -> PolynomialCoeff {
+        let mut coeffs = self.coeffs.clone();
+        if coeffs.len() < other.coeffs.len() {
+            coeffs.resize(other.coeffs.len(), blstrs::Scalar::zero());
+        }
+        for (coeff, other_coeff) in coeffs.iter_mut().zip(&other.coeffs) {
+            *coeff -= other_coeff;
+        }
+        PolynomialCoeff { coeffs }
+    }
+}
+
+impl Mul for &PolynomialCoeff {
+    type Output = PolynomialCoeff;
+
+    // Schoolbook multiplication
+    fn mul(self, other: &PolynomialCoeff) -> PolynomialCoeff {
+        if self.coeffs.is_empty() || other.coeffs.is_empty() {
+            return PolynomialCoeff::zero();
+        }
+
+        let mut coeffs = vec![blstrs::Scalar::zero(); self.coeffs.len() + other.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in other.coeffs.iter().enumerate() {
+                coeffs[i + j] += a * b;
+            }
+        }
+        PolynomialCoeff { coeffs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn random_poly(num_coeffs: usize) -> PolynomialCoeff {
+        let coeffs = (0..num_coeffs).map(|_| blstrs::Scalar::random(&mut rand::thread_rng())).collect();
+        PolynomialCoeff::new(coeffs)
+    }
+
+    #[test]
+    fn arithmetic_matches_evaluation() {
+        let a = random_poly(7);
+        let b = random_poly(4);
+        let z = blstrs::Scalar::random(&mut rand::thread_rng());
+        let scalar = blstrs::Scalar::from(3u64);
+
+        assert_eq!((&a + &b).evaluate(z), a.evaluate(z) + b.evaluate(z));
+        assert_eq!((&b + &a).evaluate(z), a.evaluate(z) + b.evaluate(z));
+        assert_eq!((&a - &b).evaluate(z), a.evaluate(z) - b.evaluate(z));
+        assert_eq!((&b - &a).evaluate(z), b.evaluate(z) - a.evaluate(z));
+        assert_eq!((&a * &b).evaluate(z), a.evaluate(z) * b.evaluate(z));
+        assert_eq!(a.scale(scalar).evaluate(z), a.evaluate(z) * scalar);
+
+        assert_eq!((&a * &b).degree(), 9);
+        assert!((&a - &a).is_zero());
+        assert_eq!((&a - &a).degree(), 0);
+    }
+
+    #[test]
+    fn divide_by_linear() {
+        let poly = random_poly(9);
+        let z = blstrs::Scalar::random(&mut rand::thread_rng());
+
+        let (quotient, remainder) = poly.divide_by_linear(z);
+        assert_eq!(remainder, poly.evaluate(z));
+
+        // poly = quotient * (X - z) + remainder
+        let linear = PolynomialCoeff::vanishing(&[z]);
+        let reconstructed = &(&quotient * &linear) + &PolynomialCoeff::new(vec![remainder]);
+        assert_eq!(reconstructed, poly);
+    }
+
+    #[test]
+    fn divide_by_binomial() {
+        let poly = random_poly(20);
+        let h = blstrs::Scalar::random(&mut rand::thread_rng());
+        let degree = 4;
+
+        let (quotient, remainder) = poly.divide_by_binomial(degree, h.pow_vartime([degree as u64]));
+        assert_eq!(remainder.coeffs.len(), degree);
+
+        // poly = quotient * (X^degree - h^degree) + remainder
+        let mut binomial = vec![blstrs::Scalar::zero(); degree + 1];
+        binomial[0] = -h.pow_vartime([degree as u64]);
+        binomial[degree] = blstrs::Scalar::one();
+        let reconstructed = &(&quotient * &PolynomialCoeff::new(binomial)) + &remainder;
+        assert_eq!(reconstructed, poly);
+
+        // The remainder agrees with the polynomial on the coset `h * {roots of order degree}`
+        let domain = Domain::new(degree);
+        for root in &domain.roots {
+            assert_eq!(remainder.evaluate(h * root), poly.evaluate(h * root));
+        }
+    }
+
+    #[test]
+    fn vanishing_polynomial() {
+        let points: Vec<_> = (1..=5u64).map(blstrs::Scalar::from).collect();
+        let vanishing = PolynomialCoeff::vanishing(&points);
+
+        assert_eq!(vanishing.degree(), points.len());
+        assert!(points.iter().all(|point| vanishing.evaluate(*point).is_zero_vartime()));
+        assert!(!vanishing.evaluate(blstrs::Scalar::from(6u64)).is_zero_vartime());
+    }
+
+    #[test]
+    fn interpolate() {
+        let points: Vec<_> = (1..=6u64).map(blstrs::Scalar::from).collect();
+        let poly = random_poly(points.len());
+        let values: Vec<_> = points.iter().map(|point| poly.evaluate(*point)).collect();
+
+        assert_eq!(PolynomialCoeff::interpolate(&points, &values), Ok(poly));
+        assert_eq!(PolynomialCoeff::interpolate(&[], &[]), Ok(PolynomialCoeff::zero()));
+        assert_eq!(
+            PolynomialCoeff::interpolate(&points, &values[1..]),
+            Err(KzgError::LengthMismatch { expected: 6, actual: 5 })
+        );
+        assert_eq!(
+            PolynomialCoeff::interpolate(&[points[0], points[0]], &values[..2]),
+            Err(KzgError::ZeroInversion)
+        );
+    }
+
+    #[test]
+    fn divide_by_vanishing() {
+        let poly = random_poly(12);
+        let points: Vec<_> = (0..4).map(|_| blstrs::Scalar::random(&mut rand::thread_rng())).collect();
+
+        // poly = quotient * Z(X) + remainder, where the remainder interpolates poly over the points
+        let quotient = poly.divide_by_vanishing(&points);
+        let values: Vec<_> = points.iter().map(|point| poly.evaluate(*point)).collect();
+        let remainder = PolynomialCoeff::interpolate(&points, &values).unwrap();
+        let reconstructed = &(&quotient * &PolynomialCoeff::vanishing(&points)) + &remainder;
+        assert_eq!(reconstructed, poly);
+    }
+
+    #[test]
+    fn evaluation_form_roundtrip() {
+        let size = 16;
+        let poly = random_poly(size - 3);
+
+        for domain in [Domain::new(size), Domain::new_bit_reversed(size)] {
+            let evaluation_form = poly.to_polynomial(&domain);
+
+            let z = blstrs::Scalar::random(&mut rand::thread_rng());
+            assert_eq!(evaluation_form.evaluate(z, &domain), poly.evaluate(z));
+
+            let mut coeffs = PolynomialCoeff::from_polynomial(&evaluation_form, &domain).coeffs;
+            assert!(coeffs.split_off(poly.coeffs.len()).iter().all(|coeff| coeff.is_zero_vartime()));
+            assert_eq!(coeffs, poly.coeffs);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt;
+
+use crate::{
+    eip4844::{bytes_to_bls_field, bytes_to_g1, kzg_to_versioned_hash, Bytes32, Bytes48, FIELD_ELEMENTS_PER_BLOB},
+    error::KzgError,
+    opening_key::OpeningKey,
+    G1_POINT_SERIALIZED_SIZE, SCALAR_SERIALIZED_SIZE,
+};
+
+// The address of the point evaluation precompile
+pub const POINT_EVALUATION_PRECOMPILE_ADDRESS: u8 = 0x0A;
+// The gas cost of calling the point evaluation precompile
+pub const POINT_EVALUATION_PRECOMPILE_GAS: u64 = 50_000;
+// The input is versioned_hash || z || y || commitment || proof
+pub const POINT_EVALUATION_INPUT_SIZE: usize = 3 * SCALAR_SERIALIZED_SIZE + 2 * G1_POINT_SERIALIZED_SIZE;
+// The output is FIELD_ELEMENTS_PER_BLOB || BLS_MODULUS
+pub const POINT_EVALUATION_OUTPUT_SIZE: usize = 2 * SCALAR_SERIALIZED_SIZE;
+
+// The order of the scalar field, encoded as a big-endian integer
+pub const BLS_MODULUS: Bytes32 = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+/// Verifies that the polynomial which `versioned_hash` refers to evaluates to `y` at `z`
+///
+/// `input` is `versioned_hash || z || y || commitment || proof`. On success, the precompile
+/// returns `FIELD_ELEMENTS_PER_BLOB || BLS_MODULUS`, where both are encoded as 32 byte big-endian integers.
+/// Any error means that the call to the precompile fails and consumes all of its gas.
+pub fn point_evaluation_precompile(
+    opening_key: &OpeningKey,
+    input: &[u8],
+) -> Result<[u8; POINT_EVALUATION_OUTPUT_SIZE], PrecompileError> {
+    if input.len() != POINT_EVALUATION_INPUT_SIZE {
+        return Err(PrecompileError::InvalidInputLength {
+            expected: POINT_EVALUATION_INPUT_SIZE,
+            actual: input.len(),
+        });
+    }
+
+    let (versioned_hash, rest) = input.split_at(SCALAR_SERIALIZED_SIZE);
+    let (z_bytes, rest) = rest.split_at(SCALAR_SERIALIZED_SIZE);
+    let (y_bytes, rest) = rest.split_at(SCALAR_SERIALIZED_SIZE);
+    let (commitment_bytes, proof_bytes) = rest.split_at(G1_POINT_SERIALIZED_SIZE);
+    let commitment_bytes: &Bytes48 = commitment_bytes.try_into().unwrap();
+
+    // The whole hash is compared, so a hash with a different version byte is also a mismatch
+    if kzg_to_versioned_hash(commitment_bytes).to_bytes() != versioned_hash {
+        return Err(PrecompileError::VersionedHashMismatch);
+    }
+
+    let input_point = bytes_to_bls_field(z_bytes.try_into().unwrap())?;
+    let output_point = bytes_to_bls_field(y_bytes.try_into().unwrap())?;
+    let poly_comm = bytes_to_g1(commitment_bytes)?;
+    let witness_comm = bytes_to_g1(proof_bytes.try_into().unwrap())?;
+
+    if !opening_key.verify(input_point, output_point, poly_comm, witness_comm) {
+        return Err(PrecompileError::InvalidProof);
+    }
+
+    let mut output = [0u8; POINT_EVALUATION_OUTPUT_SIZE];
+    output[SCALAR_SERIALIZED_SIZE - 8..SCALAR_SERIALIZED_SIZE]
+        .copy_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+    output[SCALAR_SERIALIZED_SIZE..].copy_from_slice(&BLS_MODULUS);
+    Ok(output)
+}
+
+/// Errors that cause a call to the point evaluation precompile to fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrecompileError {
+    /// The input is not `POINT_EVALUATION_INPUT_SIZE` bytes
+    InvalidInputLength { expected: usize, actual: usize },
+    /// The versioned hash is not the versioned hash of the commitment
+    VersionedHashMismatch,
+    /// One of the scalars or points is not canonically encoded
+    InvalidEncoding(KzgError),
+    /// The proof does not verify
+    InvalidProof,
+}
+
+impl From<KzgError> for PrecompileError {
+    fn from(err: KzgError) -> PrecompileError {
+        PrecompileError::InvalidEncoding(err)
+    }
+}
+
+impl fmt::Display for PrecompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrecompileError::InvalidInputLength { expected, actual } => {
+                write!(f, "invalid input length, expected {expected} bytes but got {actual}")
+            }
+            PrecompileError::VersionedHashMismatch => {
+                write!(f, "the versioned hash does not match the commitment")
+            }
+            PrecompileError::InvalidEncoding(err) => write!(f, "invalid input encoding : {err}"),
+            PrecompileError::InvalidProof => write!(f, "the proof is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for PrecompileError {}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+
+    use super::*;
+
+    #[test]
+    fn bls_modulus() {
+        // The modulus minus one is the largest canonical scalar
+        let mut modulus_minus_one = BLS_MODULUS;
+        modulus_minus_one[SCALAR_SERIALIZED_SIZE - 1] -= 1;
+        assert_eq!((-blstrs::Scalar::one()).to_bytes_be(), modulus_minus_one);
+    }
+}
@@ -1,5 +1,17 @@
-use crate::{commit_key::*, opening_key::*, domain::Domain, polynomial::Polynomial, utils};
+use std::fmt;
 
+use crate::{
+    commit_key::*, opening_key::*, domain::Domain, error::KzgError, polynomial::Polynomial,
+    polynomial_coeff::PolynomialCoeff, utils, G1_POINT_SERIALIZED_SIZE, SCALAR_SERIALIZED_SIZE,
+};
+
+// The number of bytes needed to represent a proof
+//
+// The polynomial commitment and quotient commitment are compressed G1 points,
+// followed by the output point as a big-endian scalar
+pub const PROOF_SERIALIZED_SIZE: usize = 2 * G1_POINT_SERIALIZED_SIZE + SCALAR_SERIALIZED_SIZE;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Proof {
     // Commitment to the polynomial that we have created a KZG proof for.
     pub polynomial_commitment: blstrs::G1Affine,
@@ -32,12 +44,166 @@ impl Proof {
             self.quotient_commitment,
         )
     }
+
+    /// Serializes the proof as `polynomial_commitment || quotient_commitment || output_point`
+    pub fn to_bytes(&self) -> [u8; PROOF_SERIALIZED_SIZE] {
+        let mut bytes = [0u8; PROOF_SERIALIZED_SIZE];
+        let (polynomial_commitment, rest) = bytes.split_at_mut(G1_POINT_SERIALIZED_SIZE);
+        let (quotient_commitment, output_point) = rest.split_at_mut(G1_POINT_SERIALIZED_SIZE);
+
+        polynomial_commitment.copy_from_slice(&self.polynomial_commitment.to_compressed());
+        quotient_commitment.copy_from_slice(&self.quotient_commitment.to_compressed());
+        output_point.copy_from_slice(&self.output_point.to_bytes_be());
+
+        bytes
+    }
+
+    /// Deserializes a proof created with `to_bytes`
+    ///
+    /// Both points must be on the curve and in the G1 subgroup, and the output point must be
+    /// less than the BLS modulus.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Proof, ProofDecodingError> {
+        if bytes.len() != PROOF_SERIALIZED_SIZE {
+            return Err(ProofDecodingError::InvalidLength {
+                expected: PROOF_SERIALIZED_SIZE,
+                actual: bytes.len(),
+            });
+        }
+
+        let (polynomial_commitment, rest) = bytes.split_at(G1_POINT_SERIALIZED_SIZE);
+        let (quotient_commitment, output_point) = rest.split_at(G1_POINT_SERIALIZED_SIZE);
+
+        let polynomial_commitment =
+            decode_g1_point(polynomial_commitment, ProofElement::PolynomialCommitment)?;
+        let quotient_commitment =
+            decode_g1_point(quotient_commitment, ProofElement::QuotientCommitment)?;
+        let output_point = Option::from(blstrs::Scalar::from_bytes_be(output_point.try_into().unwrap()))
+            .ok_or(ProofDecodingError::NonCanonicalScalar)?;
+
+        Ok(Proof { polynomial_commitment, quotient_commitment, output_point })
+    }
+}
+
+/// A proof that a committed polynomial evaluates to `output_points[i]` at `input_points[i]` for every `i`
+///
+/// The quotient commitment is a commitment to `(p(X) - I(X)) / Z_S(X)`, where `I(X)` interpolates
+/// the output points over the input points `S`, and `Z_S(X)` vanishes on `S`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiProof {
+    // Commitment to the polynomial that we have created a KZG proof for.
+    pub polynomial_commitment: blstrs::G1Affine,
+
+    // Commitment to the quotient polynomial
+    pub quotient_commitment: blstrs::G1Affine,
+
+    pub output_points: Vec<blstrs::Scalar>,
 }
 
+impl MultiProof {
+    /// Creates a proof that `poly` evaluates to `output_points[i]` at each of `input_points[i]`
+    ///
+    /// Returns `KzgError::DuplicatePoint` if an input point is repeated, since a proof for it could not be verified
+    /// by `OpeningKey::verify_multi_point`.
+    pub fn create(
+        commit_key: &CommitKeyLagrange,
+        poly: &Polynomial,
+        poly_comm: blstrs::G1Affine,
+        input_points: &[blstrs::Scalar],
+        domain: &Domain,
+    ) -> Result<MultiProof, KzgError> {
+        utils::check_distinct_points(input_points)?;
+
+        let poly_coeff = PolynomialCoeff::from_polynomial(poly, domain);
+        let output_points = input_points.iter().map(|input_point| poly_coeff.evaluate(*input_point)).collect();
+
+        // `I(X)` has a lower degree than `Z_S(X)`, so the quotient of `p(X) - I(X)` is the quotient of `p(X)`
+        let quotient = poly_coeff.divide_by_vanishing(input_points);
+        let quotient_commitment = commit_key.commit(&quotient.to_polynomial(domain));
+        Ok(MultiProof { polynomial_commitment: poly_comm, quotient_commitment, output_points })
+    }
+
+    /// See `OpeningKey::verify_multi_point` for the errors that can be returned
+    pub fn verify(&self, input_points: &[blstrs::Scalar], opening_key: &OpeningKey) -> Result<bool, KzgError> {
+        opening_key.verify_multi_point(
+            input_points,
+            &self.output_points,
+            self.polynomial_commitment,
+            self.quotient_commitment,
+        )
+    }
+}
+
+// Decodes a compressed G1 point, distinguishing between points which are not on the curve
+// and points which are not in the correct subgroup
+fn decode_g1_point(bytes: &[u8], element: ProofElement) -> Result<blstrs::G1Affine, ProofDecodingError> {
+    let bytes: &[u8; G1_POINT_SERIALIZED_SIZE] = bytes.try_into().unwrap();
+
+    // This checks that the encoding is valid and that the point is on the curve
+    let point: blstrs::G1Affine = Option::from(blstrs::G1Affine::from_compressed_unchecked(bytes))
+        .ok_or(ProofDecodingError::PointNotOnCurve(element))?;
+
+    if !bool::from(point.is_torsion_free()) {
+        return Err(ProofDecodingError::PointNotInSubgroup(element));
+    }
+
+    Ok(point)
+}
+
+/// The group elements that make up a serialized proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofElement {
+    PolynomialCommitment,
+    QuotientCommitment,
+}
+
+/// Errors that can occur when deserializing a proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofDecodingError {
+    /// The input is not `PROOF_SERIALIZED_SIZE` bytes
+    InvalidLength { expected: usize, actual: usize },
+    /// The point is not a valid compressed encoding of a point on the curve
+    PointNotOnCurve(ProofElement),
+    /// The point is on the curve, but not in the G1 subgroup
+    PointNotInSubgroup(ProofElement),
+    /// The output point is not less than the BLS modulus
+    NonCanonicalScalar,
+}
+
+impl fmt::Display for ProofElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofElement::PolynomialCommitment => write!(f, "polynomial commitment"),
+            ProofElement::QuotientCommitment => write!(f, "quotient commitment"),
+        }
+    }
+}
+
+impl fmt::Display for ProofDecodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofDecodingError::InvalidLength { expected, actual } => {
+                write!(f, "proof must be {expected} bytes, got {actual} bytes")
+            }
+            ProofDecodingError::PointNotOnCurve(element) => {
+                write!(f, "{element} is not a point on the curve")
+            }
+            ProofDecodingError::PointNotInSubgroup(element) => {
+                write!(f, "{element} is not in the G1 subgroup")
+            }
+            ProofDecodingError::NonCanonicalScalar => {
+                write!(f, "output point is not less than the BLS modulus")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProofDecodingError {}
+
 #[cfg(test)]
 mod tests {
 
     use ff::Field;
+    use group::{Curve, Group};
     use crate::params::PublicParameters;
 
     use super::*;
@@ -62,4 +228,164 @@ mod tests {
         assert!(proof.verify(input_point, &public_parameters.opening_key));
         assert!(!proof.verify(input_point + input_point, &public_parameters.opening_key));
     }
+
+    #[test]
+    fn valid_proof_within_domain() {
+
+        let size = 2usize.pow(4);
+
+        for domain in [Domain::new(size), Domain::new_bit_reversed(size)] {
+            valid_proof_at_every_root(&domain);
+        }
+    }
+
+    fn valid_proof_at_every_root(domain: &Domain) {
+        let size = domain.size();
+        let public_parameters = PublicParameters::from_secret_insecure(123456789, domain);
+
+        let poly = Polynomial::new(random_vector(size));
+        let poly_comm = public_parameters.commit_key.commit(&poly);
+
+        for (index, input_point) in domain.roots().iter().enumerate() {
+            let proof = Proof::create(&public_parameters.commit_key, &poly, poly_comm, *input_point, domain);
+            assert_eq!(proof.output_point, poly.evaluations[index]);
+            assert!(public_parameters.opening_key.verify(
+                *input_point,
+                proof.output_point,
+                proof.polynomial_commitment,
+                proof.quotient_commitment,
+            ));
+            assert!(!proof.verify(input_point + input_point, &public_parameters.opening_key));
+        }
+    }
+
+    #[test]
+    fn multi_proof() {
+        let size = 2usize.pow(6);
+
+        for domain in [Domain::new(size), Domain::new_bit_reversed(size)] {
+            let public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+            let opening_key = &public_parameters.opening_key;
+
+            let poly = Polynomial::new(random_vector(size));
+            let poly_comm = public_parameters.commit_key.commit(&poly);
+
+            // Open at some random points and some roots of the domain
+            let mut input_points = random_vector(5);
+            input_points.extend(&domain.roots()[..3]);
+            let proof =
+                MultiProof::create(&public_parameters.commit_key, &poly, poly_comm, &input_points, &domain).unwrap();
+
+            assert_eq!(proof.output_points[5], poly.evaluations[0]);
+            assert_eq!(proof.verify(&input_points, opening_key), Ok(true));
+
+            let mut wrong_outputs = proof.clone();
+            wrong_outputs.output_points[2] += blstrs::Scalar::one();
+            assert_eq!(wrong_outputs.verify(&input_points, opening_key), Ok(false));
+            assert_eq!(proof.verify(&random_vector(input_points.len()), opening_key), Ok(false));
+
+            // With one point, the multi-point proof is the same as a single point proof
+            let single = Proof::create(&public_parameters.commit_key, &poly, poly_comm, input_points[0], &domain);
+            let commit_key = &public_parameters.commit_key;
+            let multi = MultiProof::create(commit_key, &poly, poly_comm, &input_points[..1], &domain).unwrap();
+            assert_eq!(multi.quotient_commitment, single.quotient_commitment);
+            assert_eq!(multi.output_points, vec![single.output_point]);
+
+            // Invalid inputs are errors
+            assert_eq!(
+                proof.verify(&input_points[1..], opening_key),
+                Err(KzgError::LengthMismatch { expected: input_points.len() - 1, actual: input_points.len() })
+            );
+            let max_points = opening_key.g2_monomial.len() - 1;
+            let too_many = random_vector(max_points + 1);
+            let proof = MultiProof::create(commit_key, &poly, poly_comm, &too_many, &domain).unwrap();
+            assert_eq!(
+                proof.verify(&too_many, opening_key),
+                Err(KzgError::TooFewPoints { minimum: max_points + 2, actual: max_points + 1 })
+            );
+            let repeated = vec![input_points[0]; 2];
+            assert_eq!(
+                MultiProof::create(commit_key, &poly, poly_comm, &repeated, &domain),
+                Err(KzgError::DuplicatePoint(1))
+            );
+            let repeated_outputs = vec![proof.output_points[0]; 2];
+            assert_eq!(
+                opening_key.verify_multi_point(&repeated, &repeated_outputs, poly_comm, proof.quotient_commitment),
+                Err(KzgError::DuplicatePoint(1))
+            );
+        }
+    }
+
+    #[test]
+    fn proof_serialization_roundtrip() {
+
+        let size = 2usize.pow(4);
+
+        let domain = Domain::new(size);
+        let public_parameters = PublicParameters::from_secret_insecure(123456789, &domain);
+
+        let poly = Polynomial::new(random_vector(size));
+        let poly_comm = public_parameters.commit_key.commit(&poly);
+
+        let input_point = blstrs::Scalar::from(123456u64);
+        let proof = Proof::create(&public_parameters.commit_key, &poly, poly_comm, input_point, &domain);
+
+        let bytes = proof.to_bytes();
+        let decoded = Proof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(decoded.verify(input_point, &public_parameters.opening_key));
+    }
+
+    #[test]
+    fn invalid_proof_bytes() {
+
+        let point = blstrs::G1Projective::random(&mut rand::thread_rng()).to_affine();
+        let proof = Proof {
+            polynomial_commitment: point,
+            quotient_commitment: point,
+            output_point: blstrs::Scalar::one(),
+        };
+        let bytes = proof.to_bytes();
+
+        assert_eq!(
+            Proof::from_bytes(&bytes[1..]),
+            Err(ProofDecodingError::InvalidLength {
+                expected: PROOF_SERIALIZED_SIZE,
+                actual: PROOF_SERIALIZED_SIZE - 1
+            })
+        );
+
+        // The output point is set to the BLS modulus
+        let mut non_canonical = bytes;
+        let modulus_minus_one = (-blstrs::Scalar::one()).to_bytes_be();
+        non_canonical[2 * G1_POINT_SERIALIZED_SIZE..].copy_from_slice(&modulus_minus_one);
+        non_canonical[PROOF_SERIALIZED_SIZE - 1] += 1;
+        assert_eq!(Proof::from_bytes(&non_canonical), Err(ProofDecodingError::NonCanonicalScalar));
+
+        // The compression flag is not set
+        let mut not_on_curve = bytes;
+        not_on_curve[0] &= 0x7f;
+        assert_eq!(
+            Proof::from_bytes(&not_on_curve),
+            Err(ProofDecodingError::PointNotOnCurve(ProofElement::PolynomialCommitment))
+        );
+
+        // Find a point that is on the curve, but is not in the prime order subgroup
+        let not_in_subgroup = (0u8..)
+            .map(|x| {
+                let mut encoding = [0u8; G1_POINT_SERIALIZED_SIZE];
+                encoding[0] = 0x80;
+                encoding[G1_POINT_SERIALIZED_SIZE - 1] = x;
+                encoding
+            })
+            .find(|encoding| blstrs::G1Affine::from_compressed_unchecked(encoding).is_some().into())
+            .unwrap();
+        let mut bytes_not_in_subgroup = bytes;
+        bytes_not_in_subgroup[G1_POINT_SERIALIZED_SIZE..2 * G1_POINT_SERIALIZED_SIZE]
+            .copy_from_slice(&not_in_subgroup);
+        assert_eq!(
+            Proof::from_bytes(&bytes_not_in_subgroup),
+            Err(ProofDecodingError::PointNotInSubgroup(ProofElement::QuotientCommitment))
+        );
+    }
 }
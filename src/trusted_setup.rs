@@ -0,0 +1,305 @@
+use std::{fmt, path::Path};
+
+use group::prime::PrimeCurveAffine;
+
+use crate::{
+    commit_key::*, domain::Domain, error::KzgError, opening_key::OpeningKey, utils::bit_reversal_permutation,
+    G1_POINT_SERIALIZED_SIZE, G2_POINT_SERIALIZED_SIZE,
+};
+
+/// The output of the Ethereum KZG ceremony, in the `trusted_setup.txt` format.
+///
+/// The file contains, one item per line:
+/// - The number of G1 points
+/// - The number of G2 points
+/// - The G1 points in lagrange form
+/// - The G2 points in monomial form
+/// - Optionally, the G1 points in monomial form
+///
+/// Points are hex encoded and compressed.
+#[derive(Debug, Clone)]
+pub struct TrustedSetup {
+    /// Group elements of the form `{ \L_i(\tau) * G1 }`, in natural order
+    pub g1_lagrange: Vec<blstrs::G1Affine>,
+    /// Group elements of the form `{ \tau^i * G2 }`
+    pub g2_monomial: Vec<blstrs::G2Affine>,
+    /// Group elements of the form `{ \tau^i * G1 }`, only present in newer files
+    pub g1_monomial: Option<Vec<blstrs::G1Affine>>,
+}
+
+/// Errors that can occur when loading a trusted setup
+///
+/// Line numbers start from 1
+#[derive(Debug)]
+pub enum TrustedSetupError {
+    /// The file could not be read
+    Io(std::io::Error),
+    /// The file ended before all of the points were read
+    UnexpectedEof { line: usize },
+    /// One of the point counts at the start of the file is not a number
+    InvalidCount { line: usize },
+    /// The number of G1 points must be a power of two, greater than one
+    InvalidNumG1Points(usize),
+    /// There must be at least two G2 points; the generator and `\tau` times the generator
+    InvalidNumG2Points(usize),
+    /// The line is not a hex string of the expected length
+    InvalidHex { line: usize },
+    /// The line does not encode a point on the curve and in the G1 subgroup
+    InvalidG1Point { line: usize },
+    /// The line does not encode a point on the curve and in the G2 subgroup
+    InvalidG2Point { line: usize },
+    /// There are more lines after the last expected point
+    TrailingData { line: usize },
+    /// The setup cannot be used with the domain it was paired with
+    DomainMismatch(KzgError),
+}
+
+impl fmt::Display for TrustedSetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrustedSetupError::Io(err) => write!(f, "could not read trusted setup: {err}"),
+            TrustedSetupError::UnexpectedEof { line } => write!(f, "unexpected end of file at line {line}"),
+            TrustedSetupError::InvalidCount { line } => write!(f, "invalid point count at line {line}"),
+            TrustedSetupError::InvalidNumG1Points(num) => {
+                write!(f, "number of G1 points must be a power of two greater than one, got {num}")
+            }
+            TrustedSetupError::InvalidNumG2Points(num) => {
+                write!(f, "number of G2 points must be at least two, got {num}")
+            }
+            TrustedSetupError::InvalidHex { line } => write!(f, "invalid hex encoding at line {line}"),
+            TrustedSetupError::InvalidG1Point { line } => write!(f, "invalid G1 point at line {line}"),
+            TrustedSetupError::InvalidG2Point { line } => write!(f, "invalid G2 point at line {line}"),
+            TrustedSetupError::TrailingData { line } => write!(f, "unexpected data at line {line}"),
+            TrustedSetupError::DomainMismatch(err) => write!(f, "trusted setup does not match the domain: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for TrustedSetupError {}
+
+impl From<std::io::Error> for TrustedSetupError {
+    fn from(err: std::io::Error) -> Self {
+        TrustedSetupError::Io(err)
+    }
+}
+
+impl From<KzgError> for TrustedSetupError {
+    fn from(err: KzgError) -> Self {
+        TrustedSetupError::DomainMismatch(err)
+    }
+}
+
+impl TrustedSetup {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<TrustedSetup, TrustedSetupError> {
+        let contents = std::fs::read_to_string(path)?;
+        TrustedSetup::parse(&contents)
+    }
+
+    /// Parses a trusted setup, checking that every point is in the correct subgroup
+    pub fn parse(contents: &str) -> Result<TrustedSetup, TrustedSetupError> {
+        // Blank lines are skipped, but we keep track of the line numbers for error messages
+        let mut lines = contents
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+        let mut last_line = 0;
+        let mut next_line = || match lines.next() {
+            Some((line_number, line)) => {
+                last_line = line_number;
+                Ok((line_number, line))
+            }
+            None => Err(TrustedSetupError::UnexpectedEof { line: last_line + 1 }),
+        };
+
+        let mut read_count = || {
+            let (line_number, line) = next_line()?;
+            line.parse::<usize>().map_err(|_| TrustedSetupError::InvalidCount { line: line_number })
+        };
+        let num_g1_points = read_count()?;
+        let num_g2_points = read_count()?;
+
+        if num_g1_points < 2 || !num_g1_points.is_power_of_two() {
+            return Err(TrustedSetupError::InvalidNumG1Points(num_g1_points));
+        }
+        if num_g2_points < 2 {
+            return Err(TrustedSetupError::InvalidNumG2Points(num_g2_points));
+        }
+
+        let read_g1_point = |line_number: usize, line: &str| {
+            let bytes = decode_hex::<G1_POINT_SERIALIZED_SIZE>(line)
+                .ok_or(TrustedSetupError::InvalidHex { line: line_number })?;
+            Option::from(blstrs::G1Affine::from_compressed(&bytes))
+                .ok_or(TrustedSetupError::InvalidG1Point { line: line_number })
+        };
+
+        let g1_lagrange = (0..num_g1_points)
+            .map(|_| next_line().and_then(|(line_number, line)| read_g1_point(line_number, line)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let g2_monomial = (0..num_g2_points)
+            .map(|_| {
+                let (line_number, line) = next_line()?;
+                let bytes = decode_hex::<G2_POINT_SERIALIZED_SIZE>(line)
+                    .ok_or(TrustedSetupError::InvalidHex { line: line_number })?;
+                Option::from(blstrs::G2Affine::from_compressed(&bytes))
+                    .ok_or(TrustedSetupError::InvalidG2Point { line: line_number })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Older files end after the G2 points, newer files also contain the G1 points in monomial form
+        let g1_monomial = match next_line() {
+            Err(_) => None,
+            Ok((line_number, line)) => {
+                let mut g1_monomial = Vec::with_capacity(num_g1_points);
+                g1_monomial.push(read_g1_point(line_number, line)?);
+                for _ in 1..num_g1_points {
+                    let (line_number, line) = next_line()?;
+                    g1_monomial.push(read_g1_point(line_number, line)?);
+                }
+                Some(g1_monomial)
+            }
+        };
+
+        if let Ok((line_number, _)) = next_line() {
+            return Err(TrustedSetupError::TrailingData { line: line_number });
+        }
+
+        Ok(TrustedSetup { g1_lagrange, g2_monomial, g1_monomial })
+    }
+
+    /// The key used to commit to polynomials in lagrange form over `domain`
+    ///
+    /// The lagrange points are stored in the same order as the roots of `domain`.
+    /// Returns an error, if the size of `domain` is not the number of lagrange points.
+    pub fn commit_key(&self, domain: &Domain) -> Result<CommitKeyLagrange, KzgError> {
+        if self.g1_lagrange.len() != domain.size() {
+            return Err(KzgError::LengthMismatch { expected: self.g1_lagrange.len(), actual: domain.size() });
+        }
+
+        if domain.is_bit_reversed() {
+            Ok(CommitKeyLagrange::new(bit_reversal_permutation(&self.g1_lagrange)))
+        } else {
+            Ok(CommitKeyLagrange::new(self.g1_lagrange.clone()))
+        }
+    }
+
+    /// The key used to commit to polynomials in monomial form, if the file contained it
+    pub fn monomial_commit_key(&self) -> Option<CommitKey> {
+        self.g1_monomial.clone().map(CommitKey::new)
+    }
+
+    pub fn opening_key(&self) -> OpeningKey {
+        OpeningKey::from_g2_monomial(blstrs::G1Affine::generator(), self.g2_monomial.clone())
+    }
+}
+
+// Decodes a hex string into exactly `N` bytes
+pub(crate) fn decode_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    let hex = hex.as_bytes();
+    if hex.len() != 2 * N {
+        return None;
+    }
+
+    let nibble = |c: u8| (c as char).to_digit(16).map(|digit| digit as u8);
+
+    let mut bytes = [0u8; N];
+    for (byte, pair) in bytes.iter_mut().zip(hex.chunks_exact(2)) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use group::Curve;
+
+    use crate::{domain::Domain, polynomial::Polynomial};
+
+    use super::*;
+
+    const TRUSTED_SETUP_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trusted_setup.txt");
+
+    #[test]
+    fn load_ethereum_trusted_setup() {
+        let setup = TrustedSetup::from_file(TRUSTED_SETUP_PATH).unwrap();
+
+        assert_eq!(setup.g1_lagrange.len(), 4096);
+        assert_eq!(setup.g2_monomial.len(), 65);
+
+        // The lagrange polynomials sum to one, so the commitment to the constant polynomial 1 is the generator
+        let lagrange_sum = setup
+            .g1_lagrange
+            .iter()
+            .fold(blstrs::G1Projective::from(blstrs::G1Affine::identity()), |acc, point| acc + point);
+        assert_eq!(lagrange_sum.to_affine(), blstrs::G1Affine::generator());
+
+        let g1_monomial = setup.g1_monomial.unwrap();
+        assert_eq!(g1_monomial.len(), 4096);
+        assert_eq!(g1_monomial[0], blstrs::G1Affine::generator());
+        assert_eq!(setup.g2_monomial[0], blstrs::G2Affine::generator());
+    }
+
+    #[test]
+    fn lagrange_matches_ceremony() {
+        let setup = TrustedSetup::from_file(TRUSTED_SETUP_PATH).unwrap();
+
+        let size = setup.g1_lagrange.len();
+        for domain in [Domain::new(size), Domain::new_bit_reversed(size)] {
+            let commit_key = setup.monomial_commit_key().unwrap().into_lagrange(&domain);
+
+            let poly = Polynomial::new((0..domain.size() as u64).map(blstrs::Scalar::from).collect());
+            assert_eq!(commit_key.commit(&poly), setup.commit_key(&domain).unwrap().commit(&poly));
+        }
+
+        assert_eq!(
+            setup.commit_key(&Domain::new(size / 2)).err(),
+            Some(KzgError::LengthMismatch { expected: size, actual: size / 2 })
+        );
+    }
+
+    #[test]
+    fn malformed_trusted_setup() {
+        let g1_gen = encode_hex(&blstrs::G1Affine::generator().to_compressed());
+        let g2_gen = encode_hex(&blstrs::G2Affine::generator().to_compressed());
+        let valid_prefix = format!("2\n2\n{g1_gen}\n{g1_gen}\n{g2_gen}\n{g2_gen}\n");
+
+        let setup = TrustedSetup::parse(&valid_prefix).unwrap();
+        assert!(setup.g1_monomial.is_none());
+
+        let with_monomial = format!("{valid_prefix}{g1_gen}\n{g1_gen}\n");
+        assert!(TrustedSetup::parse(&with_monomial).unwrap().g1_monomial.is_some());
+
+        assert!(matches!(TrustedSetup::parse("two\n2\n"), Err(TrustedSetupError::InvalidCount { line: 1 })));
+        assert!(matches!(TrustedSetup::parse("3\n2\n"), Err(TrustedSetupError::InvalidNumG1Points(3))));
+        assert!(matches!(TrustedSetup::parse("2\n1\n"), Err(TrustedSetupError::InvalidNumG2Points(1))));
+        assert!(matches!(
+            TrustedSetup::parse(&format!("2\n2\n{g1_gen}\n")),
+            Err(TrustedSetupError::UnexpectedEof { line: 4 })
+        ));
+        assert!(matches!(
+            TrustedSetup::parse(&format!("2\n2\n{g1_gen}\nzz\n")),
+            Err(TrustedSetupError::InvalidHex { line: 4 })
+        ));
+        assert!(matches!(
+            TrustedSetup::parse(&format!("2\n2\n{g1_gen}\n{}\n", "00".repeat(G1_POINT_SERIALIZED_SIZE))),
+            Err(TrustedSetupError::InvalidG1Point { line: 4 })
+        ));
+        assert!(matches!(
+            TrustedSetup::parse(&format!(
+                "2\n2\n{g1_gen}\n{g1_gen}\n{g2_gen}\n{}\n",
+                "00".repeat(G2_POINT_SERIALIZED_SIZE)
+            )),
+            Err(TrustedSetupError::InvalidG2Point { line: 6 })
+        ));
+        assert!(matches!(
+            TrustedSetup::parse(&format!("{with_monomial}{g1_gen}\n")),
+            Err(TrustedSetupError::TrailingData { line: 9 })
+        ));
+    }
+
+    fn encode_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
@@ -1,6 +1,8 @@
-use crate::{domain::Domain, polynomial::Polynomial};
+use crate::{domain::Domain, error::KzgError, polynomial::Polynomial};
 
 use ff::Field;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// Computes the quotient polynomial for a kzg proof
 ///
@@ -40,7 +42,7 @@ fn compute_quotient_in_domain(
         .map(|root| root - input_point)
         .collect();
     denominator_poly[index_in_domain] = blstrs::Scalar::one();
-    serial_batch_inversion(&mut denominator_poly);
+    batch_inversion(&mut denominator_poly);
 
     let mut quotient_poly = vec![blstrs::Scalar::zero(); domain.size()];
     for i in 0..domain.size() {
@@ -65,21 +67,30 @@ fn compute_quotient_eval_within_domain(
     // TODO: should we use a special Index struct/enum to encode this?
     let input_point = domain[index_in_domain];
 
-    // TODO: optimize with batch_inverse
+    // Compute all of the denominators `z * (z - root_i)` and invert them at once
+    let mut denominators: Vec<_> = domain
+        .roots()
+        .iter()
+        .map(|root| input_point * (input_point - root))
+        .collect();
+    // The term at `index_in_domain` is skipped below, so we set it to one to avoid inverting zero
+    denominators[index_in_domain] = blstrs::Scalar::one();
+    batch_inversion(&mut denominators);
+
     let mut result = blstrs::Scalar::zero();
-    for (index, root) in domain.roots().iter().enumerate() {
+    for (index, (root, denominator_inv)) in domain.roots().iter().zip(&denominators).enumerate() {
         if index == index_in_domain {
             continue;
         }
 
         let f_i = poly[index] - output_point;
         let numerator = f_i * root;
-        let denominator = input_point * (input_point - root);
-        result += numerator * denominator.invert().unwrap()
+        result += numerator * denominator_inv
     }
 
-    todo!()
+    result
 }
+
 fn compute_quotient_outside_domain(
     poly: &Polynomial,
     input_point: blstrs::Scalar,
@@ -93,7 +104,7 @@ fn compute_quotient_outside_domain(
         .map(|domain_element| *domain_element - input_point)
         .collect();
     // This should not panic, since we assume `input_point` is not in the domain
-    serial_batch_inversion(&mut quotient);
+    batch_inversion(&mut quotient);
 
     // Compute the numerator polynomial and multiply it by the quotient which holds the
     // denominator
@@ -114,13 +125,95 @@ fn compute_quotient_outside_domain(
     Polynomial::new(quotient)
 }
 
+/// Computes the first `num_powers` powers of `base`, starting with `base^0`
+pub fn compute_powers(base: blstrs::Scalar, num_powers: usize) -> Vec<blstrs::Scalar> {
+    let mut powers = Vec::with_capacity(num_powers);
+    let mut current_power = blstrs::Scalar::one();
+    for _ in 0..num_powers {
+        powers.push(current_power);
+        current_power *= base;
+    }
+    powers
+}
+
+/// Reverses the lowest `log2(order)` bits of `index`
+///
+/// Panics, if `order` is not a power of two
+pub fn reverse_bits(index: usize, order: usize) -> usize {
+    assert!(order.is_power_of_two(), "the order must be a power of two, order is : {order}");
+    // Shifting by the full width of `usize` overflows, which happens when `order` is 1
+    index.reverse_bits().checked_shr(usize::BITS - order.trailing_zeros()).unwrap_or(0)
+}
+
+/// Permutes `sequence` by moving the element at index `i` to index `reverse_bits(i, sequence.len())`
+///
+/// The permutation is its own inverse.
+/// Panics, if the length of `sequence` is not a power of two
+pub fn bit_reversal_permutation<T: Clone>(sequence: &[T]) -> Vec<T> {
+    (0..sequence.len())
+        .map(|index| sequence[reverse_bits(index, sequence.len())].clone())
+        .collect()
+}
 
 use std::ops::MulAssign;
 
+/// Given a vector of field elements {v_i}, compute the vector {v_i^(-1)}
+///
+/// With the `parallel` feature, `v` is split into one chunk per thread and each chunk
+/// is inverted using `serial_batch_inversion`.
+///
+/// Panics, if any of the elements are zero. See `try_batch_inversion` for a non-panicking version
+pub fn batch_inversion(v: &mut [blstrs::Scalar]) {
+    try_batch_inversion(v).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Given a vector of field elements {v_i}, compute the vector {v_i^(-1)}
+///
+/// Returns an error, if any of the elements are zero. `v` is left unmodified in that case
+pub fn try_batch_inversion(v: &mut [blstrs::Scalar]) -> Result<(), KzgError> {
+    #[cfg(feature = "parallel")]
+    {
+        // Check for zero up front, since the chunks before it would otherwise already be inverted
+        if v.par_iter().any(|f| f.is_zero_vartime()) {
+            return Err(KzgError::ZeroInversion);
+        }
+        v.par_chunks_mut(parallel_chunk_size(v.len())).try_for_each(try_serial_batch_inversion)
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    try_serial_batch_inversion(v)
+}
+
+/// Returns an error with the position of the first point which repeats an earlier one
+pub(crate) fn check_distinct_points(points: &[blstrs::Scalar]) -> Result<(), KzgError> {
+    let mut seen = std::collections::HashSet::with_capacity(points.len());
+    match points.iter().position(|point| !seen.insert(point.to_bytes_le())) {
+        Some(position) => Err(KzgError::DuplicatePoint(position)),
+        None => Ok(()),
+    }
+}
+
+/// Returns the chunk size needed to split `len` elements evenly between the threads in the rayon pool
+#[cfg(feature = "parallel")]
+pub(crate) fn parallel_chunk_size(len: usize) -> usize {
+    let num_threads = rayon::current_num_threads();
+    // Chunks must not be empty
+    len.div_ceil(num_threads).max(1)
+}
+
 /// Given a vector of field elements {v_i}, compute the vector {coeff * v_i^(-1)}
 /// This method is explicitly single core.
+///
+/// Panics, if any of the elements are zero. See `try_serial_batch_inversion` for a non-panicking version
 pub fn serial_batch_inversion(v: &mut [blstrs::Scalar]) {
-    
+    try_serial_batch_inversion(v).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Given a vector of field elements {v_i}, compute the vector {coeff * v_i^(-1)}
+///
+/// Returns an error, if any of the elements are zero. `v` is left unmodified in that case
+pub fn try_serial_batch_inversion(v: &mut [blstrs::Scalar]) -> Result<(), KzgError> {
+
     // Montgomery’s Trick and Fast Implementation of Masked AES
     // Genelle, Prouff and Quisquater
     // Section 3.2
@@ -134,7 +227,9 @@ pub fn serial_batch_inversion(v: &mut [blstrs::Scalar]) {
         prod.push(tmp);
     }
 
-    assert_eq!(prod.len(), v.len(), "inversion by zero is not allowed");
+    if prod.len() != v.len() {
+        return Err(KzgError::ZeroInversion);
+    }
 
     // Invert `tmp`.
     tmp = tmp.invert().unwrap(); // Guaranteed to be nonzero.
@@ -151,7 +246,50 @@ pub fn serial_batch_inversion(v: &mut [blstrs::Scalar]) {
     {
         // tmp := tmp * f; f := tmp * s = 1/f
         let new_tmp = tmp * *f;
-        *f = tmp * &s;
+        *f = tmp * s;
         tmp = new_tmp;
     }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_reversal_permutation_small() {
+        assert_eq!(bit_reversal_permutation(&[0]), vec![0]);
+        assert_eq!(bit_reversal_permutation(&[0, 1, 2, 3, 4, 5, 6, 7]), vec![0, 4, 2, 6, 1, 5, 3, 7]);
+
+        let sequence: Vec<_> = (0..64).collect();
+        assert_eq!(bit_reversal_permutation(&bit_reversal_permutation(&sequence)), sequence);
+    }
+
+    #[test]
+    fn batch_inversion_rejects_zero() {
+        let mut v = vec![blstrs::Scalar::from(2u64), blstrs::Scalar::zero(), blstrs::Scalar::from(3u64)];
+        let original = v.clone();
+        assert_eq!(try_serial_batch_inversion(&mut v), Err(KzgError::ZeroInversion));
+        assert_eq!(v, original);
+        assert_eq!(try_batch_inversion(&mut v), Err(KzgError::ZeroInversion));
+        assert_eq!(v, original);
+
+        let mut v = vec![blstrs::Scalar::from(2u64), blstrs::Scalar::from(3u64)];
+        try_serial_batch_inversion(&mut v).unwrap();
+        assert_eq!(v[0], blstrs::Scalar::from(2u64).invert().unwrap());
+        assert_eq!(v[1], blstrs::Scalar::from(3u64).invert().unwrap());
+    }
+
+    #[test]
+    fn batch_inversion_matches_serial() {
+        let v: Vec<_> = (1..=1000u64).map(blstrs::Scalar::from).collect();
+
+        let mut serial = v.clone();
+        serial_batch_inversion(&mut serial);
+        let mut batched = v;
+        batch_inversion(&mut batched);
+
+        assert_eq!(serial, batched);
+    }
 }
@@ -0,0 +1,164 @@
+// Compares the EIP-7594 functions against c-kzg, the reference implementation used by Ethereum clients.
+//
+// There are no vendored consensus-spec vectors for these functions, so both implementations are run
+// on the same random inputs with the mainnet trusted setup, and the outputs must match byte for byte.
+use std::path::Path;
+
+use ff::Field;
+use rust_protodanksharding_example::{
+    domain::Domain,
+    eip4844::{blob_to_kzg_commitment, Blob, BYTES_PER_BLOB, FIELD_ELEMENTS_PER_BLOB},
+    eip7594::{
+        compute_cells_and_kzg_proofs, recover_cells_and_kzg_proofs, verify_cell_kzg_proof_batch, CellIndex, CELLS_PER_EXT_BLOB,
+        FIELD_ELEMENTS_PER_EXT_BLOB,
+    },
+    params::PublicParameters,
+    trusted_setup::TrustedSetup,
+    SCALAR_SERIALIZED_SIZE,
+};
+
+const TRUSTED_SETUP_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trusted_setup.txt");
+
+struct Setup {
+    public_parameters: PublicParameters,
+    domain: Domain,
+    extended_domain: Domain,
+    c_kzg_settings: c_kzg::KzgSettings,
+}
+
+fn setup() -> Setup {
+    let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+    let extended_domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_EXT_BLOB);
+    let public_parameters = PublicParameters::from_trusted_setup_file(TRUSTED_SETUP_PATH, &domain).unwrap();
+    let c_kzg_settings = c_kzg::KzgSettings::load_trusted_setup_file(Path::new(TRUSTED_SETUP_PATH), 0).unwrap();
+    Setup { public_parameters, domain, extended_domain, c_kzg_settings }
+}
+
+fn random_blob() -> Box<Blob> {
+    let mut blob = Box::new([0u8; BYTES_PER_BLOB]);
+    for chunk in blob.chunks_exact_mut(SCALAR_SERIALIZED_SIZE) {
+        chunk.copy_from_slice(&blstrs::Scalar::random(&mut rand::thread_rng()).to_bytes_be());
+    }
+    blob
+}
+
+#[test]
+fn compute_cells_and_kzg_proofs_matches_c_kzg() {
+    let setup = setup();
+    let blob = random_blob();
+
+    let c_kzg_blob = c_kzg::Blob::from_bytes(blob.as_slice()).unwrap();
+    let (c_kzg_cells, c_kzg_proofs) = setup.c_kzg_settings.compute_cells_and_kzg_proofs(&c_kzg_blob).unwrap();
+
+    // Compute the proofs both one at a time and with FK20
+    let mut public_parameters = setup.public_parameters;
+    for precompute in [false, true] {
+        if precompute {
+            let trusted_setup = TrustedSetup::from_file(TRUSTED_SETUP_PATH).unwrap();
+            let monomial_commit_key = trusted_setup.monomial_commit_key().unwrap();
+            public_parameters.precompute_cell_proofs(&monomial_commit_key, &setup.extended_domain).unwrap();
+        }
+
+        let (cells, proofs) =
+            compute_cells_and_kzg_proofs(&public_parameters, &setup.domain, &setup.extended_domain, &blob).unwrap();
+
+        assert_eq!(cells.len(), c_kzg_cells.len());
+        for (cell, c_kzg_cell) in cells.iter().zip(c_kzg_cells.iter()) {
+            assert_eq!(*cell, c_kzg_cell.to_bytes());
+        }
+        assert_eq!(proofs.len(), c_kzg_proofs.len());
+        for (proof, c_kzg_proof) in proofs.iter().zip(c_kzg_proofs.iter()) {
+            assert_eq!(*proof, *c_kzg_proof.to_bytes());
+        }
+    }
+}
+
+#[test]
+fn verify_cell_kzg_proof_batch_matches_c_kzg() {
+    let setup = setup();
+
+    let mut commitments = Vec::new();
+    let mut cell_indices = Vec::new();
+    let mut cells = Vec::new();
+    let mut proofs = Vec::new();
+    for _ in 0..2 {
+        let blob = random_blob();
+        let commitment = blob_to_kzg_commitment(&setup.public_parameters, &blob).unwrap();
+        let (blob_cells, blob_proofs) =
+            compute_cells_and_kzg_proofs(&setup.public_parameters, &setup.domain, &setup.extended_domain, &blob)
+                .unwrap();
+        for cell_index in (0..CELLS_PER_EXT_BLOB).step_by(9) {
+            commitments.push(commitment);
+            cell_indices.push(cell_index as CellIndex);
+            cells.push(blob_cells[cell_index]);
+            proofs.push(blob_proofs[cell_index]);
+        }
+    }
+
+    let mut wrong_proofs = proofs.clone();
+    wrong_proofs.swap(1, 2);
+
+    for (proofs, valid) in [(proofs, true), (wrong_proofs, false)] {
+        let got = verify_cell_kzg_proof_batch(
+            &setup.public_parameters,
+            &setup.domain,
+            &setup.extended_domain,
+            &commitments,
+            &cell_indices,
+            &cells,
+            &proofs,
+        )
+        .unwrap();
+
+        let to_c_kzg_bytes48 = |bytes: &[u8; 48]| c_kzg::Bytes48::from_bytes(bytes).unwrap();
+        let c_kzg_commitments: Vec<_> = commitments.iter().map(to_c_kzg_bytes48).collect();
+        let c_kzg_proofs: Vec<_> = proofs.iter().map(to_c_kzg_bytes48).collect();
+        let c_kzg_cells: Vec<_> = cells.iter().map(|cell| c_kzg::Cell::from_bytes(cell).unwrap()).collect();
+        let expected = setup
+            .c_kzg_settings
+            .verify_cell_kzg_proof_batch(&c_kzg_commitments, &cell_indices, &c_kzg_cells, &c_kzg_proofs)
+            .unwrap();
+
+        assert_eq!(got, valid);
+        assert_eq!(got, expected);
+    }
+}
+
+#[test]
+fn recover_cells_and_kzg_proofs_matches_c_kzg() {
+    let mut setup = setup();
+    let trusted_setup = TrustedSetup::from_file(TRUSTED_SETUP_PATH).unwrap();
+    let monomial_commit_key = trusted_setup.monomial_commit_key().unwrap();
+    setup.public_parameters.precompute_cell_proofs(&monomial_commit_key, &setup.extended_domain).unwrap();
+
+    let blob = random_blob();
+    let (cells, _) =
+        compute_cells_and_kzg_proofs(&setup.public_parameters, &setup.domain, &setup.extended_domain, &blob).unwrap();
+
+    // Keep every other cell, and a few more
+    let cell_indices: Vec<CellIndex> =
+        (0..CELLS_PER_EXT_BLOB as CellIndex).filter(|cell_index| cell_index % 2 == 1 || cell_index % 10 == 0).collect();
+    let known_cells: Vec<_> = cell_indices.iter().map(|cell_index| cells[*cell_index as usize]).collect();
+
+    let (recovered_cells, recovered_proofs) = recover_cells_and_kzg_proofs(
+        &setup.public_parameters,
+        &setup.domain,
+        &setup.extended_domain,
+        &cell_indices,
+        &known_cells,
+    )
+    .unwrap();
+
+    let c_kzg_cells: Vec<_> = known_cells.iter().map(|cell| c_kzg::Cell::from_bytes(cell).unwrap()).collect();
+    let (c_kzg_recovered_cells, c_kzg_recovered_proofs) =
+        setup.c_kzg_settings.recover_cells_and_kzg_proofs(&cell_indices, &c_kzg_cells).unwrap();
+
+    assert_eq!(recovered_cells.len(), c_kzg_recovered_cells.len());
+    for (cell, c_kzg_cell) in recovered_cells.iter().zip(c_kzg_recovered_cells.iter()) {
+        assert_eq!(*cell, c_kzg_cell.to_bytes());
+    }
+    assert_eq!(recovered_proofs.len(), c_kzg_recovered_proofs.len());
+    for (proof, c_kzg_proof) in recovered_proofs.iter().zip(c_kzg_recovered_proofs.iter()) {
+        assert_eq!(*proof, *c_kzg_proof.to_bytes());
+    }
+}
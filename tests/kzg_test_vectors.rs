@@ -0,0 +1,223 @@
+// Runs the `general/deneb/kzg` test vectors from ethereum/consensus-spec-tests
+//
+// The vectors are stored in `tests/kzg_test_vectors/<handler>/<case>/data.yaml`. An output of `null`
+// means that the function must return an error, which includes inputs with the wrong number of bytes.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rust_protodanksharding_example::{
+    domain::Domain,
+    eip4844::{
+        blob_to_kzg_commitment, compute_blob_kzg_proof, compute_kzg_proof, verify_blob_kzg_proof,
+        verify_blob_kzg_proof_batch, verify_kzg_proof, Blob, Bytes32, Bytes48, FIELD_ELEMENTS_PER_BLOB,
+    },
+    params::PublicParameters,
+};
+use serde::Deserialize;
+
+const TEST_VECTORS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/kzg_test_vectors");
+const TRUSTED_SETUP_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/trusted_setup.txt");
+
+#[derive(Deserialize)]
+struct TestCase<I, O> {
+    input: I,
+    output: Option<O>,
+}
+
+#[derive(Deserialize)]
+struct BlobToKzgCommitmentInput {
+    blob: String,
+}
+
+#[derive(Deserialize)]
+struct ComputeKzgProofInput {
+    blob: String,
+    z: String,
+}
+
+#[derive(Deserialize)]
+struct ComputeBlobKzgProofInput {
+    blob: String,
+    commitment: String,
+}
+
+#[derive(Deserialize)]
+struct VerifyKzgProofInput {
+    commitment: String,
+    z: String,
+    y: String,
+    proof: String,
+}
+
+#[derive(Deserialize)]
+struct VerifyBlobKzgProofInput {
+    blob: String,
+    commitment: String,
+    proof: String,
+}
+
+#[derive(Deserialize)]
+struct VerifyBlobKzgProofBatchInput {
+    blobs: Vec<String>,
+    commitments: Vec<String>,
+    proofs: Vec<String>,
+}
+
+fn setup() -> (PublicParameters, Domain) {
+    let domain = Domain::new_bit_reversed(FIELD_ELEMENTS_PER_BLOB);
+    let public_parameters = PublicParameters::from_trusted_setup_file(TRUSTED_SETUP_PATH, &domain).unwrap();
+    (public_parameters, domain)
+}
+
+// Returns the path and the parsed contents of every test case for `handler`
+fn load_test_cases<I, O>(handler: &str) -> Vec<(PathBuf, TestCase<I, O>)>
+where
+    TestCase<I, O>: serde::de::DeserializeOwned,
+{
+    let handler_dir = Path::new(TEST_VECTORS_DIR).join(handler);
+    let mut paths: Vec<_> = fs::read_dir(&handler_dir)
+        .unwrap_or_else(|err| panic!("cannot read {}: {err}", handler_dir.display()))
+        .map(|entry| entry.unwrap().path().join("data.yaml"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no test cases found for {handler}");
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let contents = fs::read_to_string(&path).unwrap();
+            let test_case = serde_yaml::from_str(&contents)
+                .unwrap_or_else(|err| panic!("cannot parse {}: {err}", path.display()));
+            (path, test_case)
+        })
+        .collect()
+}
+
+// Decodes a `0x` prefixed hex string. Returns `None` if the string is not valid hex or has the wrong length,
+// which the test vectors use for inputs that must be rejected
+fn decode_hex<const N: usize>(hex: &str) -> Option<Box<[u8; N]>> {
+    let hex = hex.strip_prefix("0x")?.as_bytes();
+    if hex.len() != 2 * N {
+        return None;
+    }
+
+    let nibble = |c: u8| (c as char).to_digit(16).map(|digit| digit as u8);
+    let bytes: Vec<u8> = hex
+        .chunks_exact(2)
+        .map(|pair| Some(nibble(pair[0])? << 4 | nibble(pair[1])?))
+        .collect::<Option<_>>()?;
+    bytes.into_boxed_slice().try_into().ok()
+}
+
+// Blobs are too large to be moved around on the stack, so they are copied straight into the vector
+fn decode_all<const N: usize>(hexes: &[String]) -> Option<Vec<[u8; N]>> {
+    let mut decoded = vec![[0u8; N]; hexes.len()];
+    for (bytes, hex) in decoded.iter_mut().zip(hexes) {
+        bytes.copy_from_slice(&*decode_hex::<N>(hex)?);
+    }
+    Some(decoded)
+}
+
+#[test]
+fn blob_to_kzg_commitment_test_vectors() {
+    let (public_parameters, _) = setup();
+
+    for (path, test_case) in load_test_cases::<BlobToKzgCommitmentInput, String>("blob_to_kzg_commitment") {
+        let result = (|| {
+            let blob: Box<Blob> = decode_hex(&test_case.input.blob)?;
+            blob_to_kzg_commitment(&public_parameters, &blob).ok()
+        })();
+        let expected = test_case.output.map(|commitment| *decode_hex::<48>(&commitment).unwrap());
+
+        assert_eq!(result, expected, "{}", path.display());
+    }
+}
+
+#[test]
+fn compute_kzg_proof_test_vectors() {
+    let (public_parameters, domain) = setup();
+
+    for (path, test_case) in load_test_cases::<ComputeKzgProofInput, [String; 2]>("compute_kzg_proof") {
+        let input = test_case.input;
+        let result = (|| {
+            let blob: Box<Blob> = decode_hex(&input.blob)?;
+            let z: Box<Bytes32> = decode_hex(&input.z)?;
+            compute_kzg_proof(&public_parameters, &domain, &blob, &z).ok()
+        })();
+        let expected =
+            test_case.output.map(|[proof, y]| (*decode_hex::<48>(&proof).unwrap(), *decode_hex::<32>(&y).unwrap()));
+
+        assert_eq!(result, expected, "{}", path.display());
+    }
+}
+
+#[test]
+fn compute_blob_kzg_proof_test_vectors() {
+    let (public_parameters, domain) = setup();
+
+    for (path, test_case) in load_test_cases::<ComputeBlobKzgProofInput, String>("compute_blob_kzg_proof") {
+        let input = test_case.input;
+        let result = (|| {
+            let blob: Box<Blob> = decode_hex(&input.blob)?;
+            let commitment = decode_hex::<48>(&input.commitment)?;
+            compute_blob_kzg_proof(&public_parameters, &domain, &blob, &commitment).ok()
+        })();
+        let expected = test_case.output.map(|proof| *decode_hex::<48>(&proof).unwrap());
+
+        assert_eq!(result, expected, "{}", path.display());
+    }
+}
+
+#[test]
+fn verify_kzg_proof_test_vectors() {
+    let (public_parameters, _) = setup();
+
+    for (path, test_case) in load_test_cases::<VerifyKzgProofInput, bool>("verify_kzg_proof") {
+        let input = test_case.input;
+        let result = (|| {
+            let commitment = decode_hex::<48>(&input.commitment)?;
+            let z = decode_hex::<32>(&input.z)?;
+            let y = decode_hex::<32>(&input.y)?;
+            let proof = decode_hex::<48>(&input.proof)?;
+            verify_kzg_proof(&public_parameters, &commitment, &z, &y, &proof).ok()
+        })();
+
+        assert_eq!(result, test_case.output, "{}", path.display());
+    }
+}
+
+#[test]
+fn verify_blob_kzg_proof_test_vectors() {
+    let (public_parameters, domain) = setup();
+
+    for (path, test_case) in load_test_cases::<VerifyBlobKzgProofInput, bool>("verify_blob_kzg_proof") {
+        let input = test_case.input;
+        let result = (|| {
+            let blob = decode_hex(&input.blob)?;
+            let commitment = decode_hex::<48>(&input.commitment)?;
+            let proof = decode_hex::<48>(&input.proof)?;
+            verify_blob_kzg_proof(&public_parameters, &domain, &blob, &commitment, &proof).ok()
+        })();
+
+        assert_eq!(result, test_case.output, "{}", path.display());
+    }
+}
+
+#[test]
+fn verify_blob_kzg_proof_batch_test_vectors() {
+    let (public_parameters, domain) = setup();
+
+    for (path, test_case) in load_test_cases::<VerifyBlobKzgProofBatchInput, bool>("verify_blob_kzg_proof_batch") {
+        let input = test_case.input;
+        let result = (|| {
+            let blobs: Vec<Blob> = decode_all(&input.blobs)?;
+            let commitments: Vec<Bytes48> = decode_all(&input.commitments)?;
+            let proofs: Vec<Bytes48> = decode_all(&input.proofs)?;
+            verify_blob_kzg_proof_batch(&public_parameters, &domain, &blobs, &commitments, &proofs).ok()
+        })();
+
+        assert_eq!(result, test_case.output, "{}", path.display());
+    }
+}